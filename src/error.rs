@@ -20,6 +20,7 @@ See the License for the specific language governing permissions and
 limitations under the License.
 */
 
+use crate::writer::LanguageStyle;
 use std::fmt::{Debug, Display};
 
 // ------------------------------------------------------------------------------------------------
@@ -38,6 +39,35 @@ pub enum Error {
     SymbolParserError {
         source: String,
     },
+    /// A pattern or template string could not be parsed.
+    ParserError {
+        source: String,
+    },
+    /// A pattern is not well-formed, for example it has more than one ellipsis in a list.
+    InvalidPattern {
+        reason: String,
+    },
+    /// A template is not well-formed, or a value cannot be spliced into it.
+    InvalidTemplate {
+        reason: String,
+    },
+    /// A template was instantiated without a value for one of its holes.
+    MissingTemplateValue {
+        name: String,
+    },
+    /// The writer was asked to wrap a value in a definition, but no name was provided.
+    MissingDefinitionName,
+    /// A shared value was written before its value was set.
+    UnsetSharedValue,
+    /// A value could not be constructed from the arguments provided.
+    InvalidValue {
+        reason: String,
+    },
+    /// The value has no representation in the selected language style.
+    UnsupportedInStyle {
+        value: String,
+        style: LanguageStyle,
+    },
 }
 
 ///
@@ -66,6 +96,71 @@ where
     }
 }
 
+/// Construct an Error from the provided source.
+#[inline]
+pub fn parser_error<S>(source: S) -> Error
+where
+    S: Into<String>,
+{
+    Error::ParserError {
+        source: source.into(),
+    }
+}
+
+/// Construct an Error for a pattern that is not well-formed.
+#[inline]
+pub fn invalid_pattern<S>(reason: S) -> Error
+where
+    S: Into<String>,
+{
+    Error::InvalidPattern {
+        reason: reason.into(),
+    }
+}
+
+/// Construct an Error for a template that is not well-formed.
+#[inline]
+pub fn invalid_template<S>(reason: S) -> Error
+where
+    S: Into<String>,
+{
+    Error::InvalidTemplate {
+        reason: reason.into(),
+    }
+}
+
+/// Construct an Error for a template hole with no value.
+#[inline]
+pub fn missing_template_value<S>(name: S) -> Error
+where
+    S: Into<String>,
+{
+    Error::MissingTemplateValue { name: name.into() }
+}
+
+/// Construct an Error for a value that could not be constructed.
+#[inline]
+pub fn invalid_value<S>(reason: S) -> Error
+where
+    S: Into<String>,
+{
+    Error::InvalidValue {
+        reason: reason.into(),
+    }
+}
+
+/// Construct an Error for a value that cannot be written in the given style.
+#[inline]
+pub fn unsupported_in_style<S>(value: S, style: LanguageStyle) -> Error
+where
+    S: Into<String>,
+{
+    Error::UnsupportedInStyle {
+        value: value.into(),
+        style,
+    }
+}
+
 // ------------------------------------------------------------------------------------------------
 // Implementations
 // ------------------------------------------------------------------------------------------------
@@ -81,6 +176,26 @@ impl Display for Error {
                     "An error occurred parsing a symbol string; source: {}",
                     source
                 ),
+                Error::ParserError { source } => format!(
+                    "An error occurred parsing an s-expression string; source: {}",
+                    source
+                ),
+                Error::InvalidPattern { reason } =>
+                    format!("The pattern is not well-formed; reason: {}", reason),
+                Error::InvalidTemplate { reason } =>
+                    format!("The template is not well-formed; reason: {}", reason),
+                Error::MissingTemplateValue { name } =>
+                    format!("No value was provided for the template hole {}", name),
+                Error::MissingDefinitionName =>
+                    "A definition form was requested but no name was provided".to_string(),
+                Error::UnsetSharedValue =>
+                    "A shared value was written before its value was set".to_string(),
+                Error::InvalidValue { reason } =>
+                    format!("The value could not be constructed; reason: {}", reason),
+                Error::UnsupportedInStyle { value, style } => format!(
+                    "The value {} cannot be represented in the language style {:?}",
+                    value, style
+                ),
             }
         )
     }
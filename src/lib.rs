@@ -7,8 +7,32 @@ More detailed description, with
 
 TBD
 
+# Macros
+
+[`sexpr!`] builds a single [`Value`] from Lisp-like syntax, and [`sexprs!`] builds a
+`Vec<Value>` from any number of top-level forms. `sexpr!` always produces a `Value`, so input with
+several forms, such as `sexpr!((define x 10) (list :a "b" #t))`, does not compile and is written
+with `sexprs!` instead.
+
+```rust
+use sexpr_out::{sexpr, sexprs, Value};
+
+let forms: Vec<Value> = sexprs!((define x 10) (list :a "b" #t));
+assert_eq!(forms[0], sexpr!((define x 10)));
+```
+
+```compile_fail
+let value = sexpr_out::sexpr!((define x 10) (list :a "b" #t));
+```
+
 # Features
 
+- **bignum** (default): adds support for arbitrary-precision integers, using the `num-bigint`
+  crate, so that values such as `u64`, `u128`, and `i128` that do not fit into an `i64` are written
+  exactly.
+- **normalization**: adds `Symbol::parse_normalized` and `Keyword::parse_normalized` which apply
+  Unicode NFC normalization, using the `unicode-normalization` crate, before parsing.
+
  */
 
 /*
@@ -72,5 +96,15 @@ pub use error::Error;
 pub mod value;
 pub use value::{Number, Value};
 
+pub mod pattern;
+
+pub mod template;
+
+pub mod visitor;
+
 pub mod writer;
 pub use writer::{Options, Writer};
+
+mod macros;
+
+mod reader;
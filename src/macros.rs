@@ -0,0 +1,274 @@
+/*!
+Provides the [`sexpr!`](crate::sexpr) and [`sexprs!`](crate::sexprs) macros for building
+[`Value`](crate::Value) trees using a Lisp-like syntax.
+
+ */
+
+/*
+Copyright 2024 Simon Johnston <johnstonskj@gmail.com>
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+// ------------------------------------------------------------------------------------------------
+// Public Macros
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Build a [`Value`](crate::Value) using a Lisp-like syntax.
+///
+/// The following forms are recognized:
+///
+/// - `(a b c)` is a list, and `(a b . c)` a dotted list.
+/// - `[a b c]` and `#(a b c)` are vectors.
+/// - `#t`, `#true`, `#f`, and `#false` are booleans, as are the Rust literals `true` and `false`.
+/// - Rust literals are converted with `Value::from`.
+/// - Identifiers are symbols and `:name` is a keyword. Identifiers joined by `->`, and ending in
+///   `?` or `!`, form a single name, so that `string->list` and `null?` are written as expected.
+///   The operators `+`, `-`, `*`, `/`, `%`, `<`, `>`, `=`, `<=`, and `>=`, as well as `_` and
+///   `...`, are also symbols.
+/// - `|...|` is a single symbol, and `:|...|` a keyword, named by the tokens between the bars
+///   joined without spaces, so that `|set-car!|` and `:|read-only|` may contain `-`.
+/// - `,expr` inserts the value of a Rust expression that implements `Into<Value>`; any expression
+///   other than a single identifier or literal must be wrapped in parentheses or braces.
+/// - `,@expr` splices the items of a Rust expression, any `IntoIterator` whose items implement
+///   `Into<Value>`, into the enclosing list or vector.
+///
+/// The input must be a single datum, resulting in a `Value`; use [`sexprs!`](crate::sexprs) to
+/// build a `Vec<Value>` from any number of top-level forms. So that the result type does not
+/// depend on the input, several forms, as in `sexpr!((define x 10) (list :a "b" #t))`, are a
+/// compile error and are written `sexprs!((define x 10) (list :a "b" #t))`.
+///
+/// As the input is tokenized by Rust, whitespace is not significant and `a-b` cannot be told
+/// apart from `a - b`. A `-` on its own is therefore always the symbol `-`: `(- 1 x)` and
+/// `(a - b)` are both lists of three items, `-1` is read as the symbol `-` followed by `1`, and a
+/// negative number is inserted with `,(-1)`. Names containing `-` are written between bars, as
+/// `|make-widget|`, and names the Rust tokenizer cannot represent, such as `|a b|`, must be
+/// inserted with `,expr`. Also, rustfmt may reformat input that is valid Rust, turning
+/// `sexpr!((1 . 2))` into `sexpr!((1.2))`; use braces, `sexpr! {(1 . 2)}`, to prevent this.
+///
+/// # Example
+///
+/// ```rust
+/// use sexpr_out::{sexpr, value::{Keyword, Symbol}, Value};
+///
+/// let width = 10;
+/// let value = sexpr!((|make-widget| :name "x" :width ,width :visible #t));
+///
+/// assert_eq!(
+///     value,
+///     Value::from(vec![
+///         Value::from(Symbol::new("make-widget")),
+///         Value::from(Keyword::new("name")),
+///         Value::from("x"),
+///         Value::from(Keyword::new("width")),
+///         Value::from(10),
+///         Value::from(Keyword::new("visible")),
+///         Value::from(true),
+///     ])
+/// );
+/// ```
+///
+#[macro_export]
+macro_rules! sexpr {
+    // -------------------------------------------------------------------------------------------
+    // Items, accumulated as `[one expr]` or `[splice expr]`.
+    // -------------------------------------------------------------------------------------------
+    (@items $kind:tt [$($acc:tt)*]) => {
+        $crate::sexpr!(@finish $kind [$($acc)*])
+    };
+    (@items $kind:tt [$($acc:tt)*] ( $($inner:tt)* ) $($rest:tt)*) => {
+        $crate::sexpr!(@items $kind [$($acc)* [one $crate::sexpr!(@items list [] $($inner)*)]] $($rest)*)
+    };
+    (@items $kind:tt [$($acc:tt)*] [ $($inner:tt)* ] $($rest:tt)*) => {
+        $crate::sexpr!(@items $kind [$($acc)* [one $crate::sexpr!(@items vector [] $($inner)*)]] $($rest)*)
+    };
+    (@items $kind:tt [$($acc:tt)*] # ( $($inner:tt)* ) $($rest:tt)*) => {
+        $crate::sexpr!(@items $kind [$($acc)* [one $crate::sexpr!(@items vector [] $($inner)*)]] $($rest)*)
+    };
+    (@items $kind:tt [$($acc:tt)*] # t $($rest:tt)*) => {
+        $crate::sexpr!(@items $kind [$($acc)* [one $crate::Value::from(true)]] $($rest)*)
+    };
+    (@items $kind:tt [$($acc:tt)*] # true $($rest:tt)*) => {
+        $crate::sexpr!(@items $kind [$($acc)* [one $crate::Value::from(true)]] $($rest)*)
+    };
+    (@items $kind:tt [$($acc:tt)*] # f $($rest:tt)*) => {
+        $crate::sexpr!(@items $kind [$($acc)* [one $crate::Value::from(false)]] $($rest)*)
+    };
+    (@items $kind:tt [$($acc:tt)*] # false $($rest:tt)*) => {
+        $crate::sexpr!(@items $kind [$($acc)* [one $crate::Value::from(false)]] $($rest)*)
+    };
+    (@items $kind:tt [$($acc:tt)*] , @ $value:tt $($rest:tt)*) => {
+        $crate::sexpr!(@items $kind [$($acc)* [splice $value]] $($rest)*)
+    };
+    (@items $kind:tt [$($acc:tt)*] , $value:tt $($rest:tt)*) => {
+        $crate::sexpr!(@items $kind [$($acc)* [one $crate::Value::from($value)]] $($rest)*)
+    };
+    (@items $kind:tt [$($acc:tt)*] : $name:ident $($rest:tt)*) => {
+        $crate::sexpr!(@name Keyword $kind [$($acc)*] [stringify!($name)] $($rest)*)
+    };
+    (@items $kind:tt [$($acc:tt)*] : | $($rest:tt)*) => {
+        $crate::sexpr!(@bars Keyword $kind [$($acc)*] [] $($rest)*)
+    };
+    (@items $kind:tt [$($acc:tt)*] | $($rest:tt)*) => {
+        $crate::sexpr!(@bars Symbol $kind [$($acc)*] [] $($rest)*)
+    };
+    // `-` is matched first, as a `literal` fragment would take it as the sign of a number.
+    (@items $kind:tt [$($acc:tt)*] - $($rest:tt)*) => {
+        $crate::sexpr!(@operator $kind [$($acc)*] - $($rest)*)
+    };
+    (@items $kind:tt [$($acc:tt)*] $value:literal $($rest:tt)*) => {
+        $crate::sexpr!(@items $kind [$($acc)* [one $crate::Value::from($value)]] $($rest)*)
+    };
+    (@items list [$($acc:tt)+] . $($rest:tt)+) => {
+        $crate::sexpr!(@items (dotted [$($acc)+]) [] $($rest)+)
+    };
+    (@items $kind:tt [$($acc:tt)*] $name:ident $($rest:tt)*) => {
+        $crate::sexpr!(@name Symbol $kind [$($acc)*] [stringify!($name)] $($rest)*)
+    };
+    (@items $kind:tt [$($acc:tt)*] + $($rest:tt)*) => {
+        $crate::sexpr!(@operator $kind [$($acc)*] + $($rest)*)
+    };
+    (@items $kind:tt [$($acc:tt)*] * $($rest:tt)*) => {
+        $crate::sexpr!(@operator $kind [$($acc)*] * $($rest)*)
+    };
+    (@items $kind:tt [$($acc:tt)*] / $($rest:tt)*) => {
+        $crate::sexpr!(@operator $kind [$($acc)*] / $($rest)*)
+    };
+    (@items $kind:tt [$($acc:tt)*] % $($rest:tt)*) => {
+        $crate::sexpr!(@operator $kind [$($acc)*] % $($rest)*)
+    };
+    (@items $kind:tt [$($acc:tt)*] < $($rest:tt)*) => {
+        $crate::sexpr!(@operator $kind [$($acc)*] < $($rest)*)
+    };
+    (@items $kind:tt [$($acc:tt)*] > $($rest:tt)*) => {
+        $crate::sexpr!(@operator $kind [$($acc)*] > $($rest)*)
+    };
+    (@items $kind:tt [$($acc:tt)*] = $($rest:tt)*) => {
+        $crate::sexpr!(@operator $kind [$($acc)*] = $($rest)*)
+    };
+    (@items $kind:tt [$($acc:tt)*] <= $($rest:tt)*) => {
+        $crate::sexpr!(@operator $kind [$($acc)*] <= $($rest)*)
+    };
+    (@items $kind:tt [$($acc:tt)*] >= $($rest:tt)*) => {
+        $crate::sexpr!(@operator $kind [$($acc)*] >= $($rest)*)
+    };
+    (@items $kind:tt [$($acc:tt)*] _ $($rest:tt)*) => {
+        $crate::sexpr!(@operator $kind [$($acc)*] _ $($rest)*)
+    };
+    (@items $kind:tt [$($acc:tt)*] ... $($rest:tt)*) => {
+        $crate::sexpr!(@operator $kind [$($acc)*] ... $($rest)*)
+    };
+    (@items $kind:tt [$($acc:tt)*] $other:tt $($rest:tt)*) => {
+        compile_error!(concat!("unexpected token in sexpr!: ", stringify!($other)))
+    };
+    (@operator $kind:tt [$($acc:tt)*] $op:tt $($rest:tt)*) => {
+        $crate::sexpr!(@items $kind [$($acc)* [one $crate::Value::from($crate::value::Symbol::new(stringify!($op)))]] $($rest)*)
+    };
+    // -------------------------------------------------------------------------------------------
+    // Names, joining the parts of `string->list` or `null?`, or everything between bars as in
+    // `|set-car!|`, into a single symbol or keyword.
+    // -------------------------------------------------------------------------------------------
+    (@name $ctor:ident $kind:tt [$($acc:tt)*] [$($parts:expr),+] -> $name:ident $($rest:tt)*) => {
+        $crate::sexpr!(@name $ctor $kind [$($acc)*] [$($parts),+, "->", stringify!($name)] $($rest)*)
+    };
+    (@name $ctor:ident $kind:tt [$($acc:tt)*] [$($parts:expr),+] ? $($rest:tt)*) => {
+        $crate::sexpr!(@name $ctor $kind [$($acc)*] [$($parts),+, "?"] $($rest)*)
+    };
+    (@name $ctor:ident $kind:tt [$($acc:tt)*] [$($parts:expr),+] ! $($rest:tt)*) => {
+        $crate::sexpr!(@name $ctor $kind [$($acc)*] [$($parts),+, "!"] $($rest)*)
+    };
+    (@name $ctor:ident $kind:tt [$($acc:tt)*] [$($parts:expr),+] $($rest:tt)*) => {
+        $crate::sexpr!(@items $kind [$($acc)* [one $crate::Value::from($crate::value::$ctor::new(concat!($($parts),+)))]] $($rest)*)
+    };
+    (@bars $ctor:ident $kind:tt [$($acc:tt)*] [$($parts:expr),+] | $($rest:tt)*) => {
+        $crate::sexpr!(@items $kind [$($acc)* [one $crate::Value::from($crate::value::$ctor::new(concat!($($parts),+)))]] $($rest)*)
+    };
+    (@bars $ctor:ident $kind:tt [$($acc:tt)*] [] | $($rest:tt)*) => {
+        compile_error!("a name between bars must not be empty")
+    };
+    (@bars $ctor:ident $kind:tt [$($acc:tt)*] [$($parts:expr),*] $part:tt $($rest:tt)*) => {
+        $crate::sexpr!(@bars $ctor $kind [$($acc)*] [$($parts,)* stringify!($part)] $($rest)*)
+    };
+    (@bars $ctor:ident $kind:tt [$($acc:tt)*] [$($parts:expr),*]) => {
+        compile_error!("a name between bars must end with `|`")
+    };
+    // -------------------------------------------------------------------------------------------
+    // Finishing, turning the accumulated items into a value.
+    // -------------------------------------------------------------------------------------------
+    (@finish list [$($acc:tt)*]) => {
+        $crate::Value::List($crate::sexpr!(@vec [$($acc)*]))
+    };
+    (@finish vector [$($acc:tt)*]) => {
+        $crate::Value::Vector($crate::sexpr!(@vec [$($acc)*]))
+    };
+    (@finish (dotted [$($acc:tt)*]) [[one $tail:expr]]) => {
+        $crate::Value::improper_list($crate::sexpr!(@vec [$($acc)*]), $tail)
+    };
+    (@finish (dotted [$($acc:tt)*]) [$($tail:tt)*]) => {
+        compile_error!("a dotted list must have exactly one value after the `.`")
+    };
+    (@finish top [[one $value:expr]]) => {
+        $value
+    };
+    (@finish top [$($acc:tt)*]) => {
+        compile_error!("sexpr! expects a single datum, use sexprs! for any number of forms")
+    };
+    (@finish forms [$($acc:tt)*]) => {
+        $crate::sexpr!(@vec [$($acc)*])
+    };
+    (@vec [$($acc:tt)*]) => {{
+        #[allow(unused_mut)]
+        let mut items: ::std::vec::Vec<$crate::Value> = ::std::vec::Vec::new();
+        $(
+            $crate::sexpr!(@push items $acc);
+        )*
+        items
+    }};
+    (@push $items:ident [one $value:expr]) => {
+        $items.push($value)
+    };
+    (@push $items:ident [splice $values:expr]) => {
+        $items.extend(::std::iter::IntoIterator::into_iter($values).map($crate::Value::from))
+    };
+    // -------------------------------------------------------------------------------------------
+    // Entry point.
+    // -------------------------------------------------------------------------------------------
+    ($($tokens:tt)+) => {
+        $crate::sexpr!(@items top [] $($tokens)+)
+    };
+}
+
+///
+/// Build a `Vec<Value>` with one [`Value`](crate::Value) for each top-level form, using the same
+/// syntax as [`sexpr!`](crate::sexpr). A top-level `,@expr` splices its items into the result.
+///
+/// # Example
+///
+/// ```rust
+/// use sexpr_out::{sexpr, sexprs};
+///
+/// let forms = sexprs! {
+///     (define x 10)
+///     (display x)
+/// };
+///
+/// assert_eq!(forms, vec![sexpr!((define x 10)), sexpr!((display x))]);
+/// ```
+///
+#[macro_export]
+macro_rules! sexprs {
+    ($($tokens:tt)*) => {
+        $crate::sexpr!(@items forms [] $($tokens)*)
+    };
+}
@@ -0,0 +1,593 @@
+/*!
+Provides [`Pattern`], for matching and destructuring [`Value`] trees.
+
+A pattern is usually parsed from a string, or built from a value, where the following are
+treated specially:
+
+- `_` matches any value.
+- `?name` matches any value and binds it to `name`; a variable that appears more than once
+  must match equal values each time. `?_` matches any value without binding it.
+- `?name:type` matches only values of the given type, one of `atom`, `boolean`, `character`,
+  `integer`, `keyword`, `list`, `map`, `number`, `pair`, `string`, `symbol`, or `vector`.
+- `pattern ...`, in a list or vector, matches zero or more items; each variable in `pattern`
+  is bound to a sequence with one entry for each item matched. Only one ellipsis is allowed in
+  each list or vector.
+- `(pattern ... . ?rest)` binds `rest` to the items following those matched by the other
+  patterns, as a list.
+
+Anything else is a literal which matches a value that is equal, as for [`Value::is_equal`].
+
+# Example
+
+```rust
+use sexpr_out::{pattern::Pattern, sexpr};
+
+let pattern: Pattern = "(define (?name:symbol ?args ...) ?body ...)".parse().unwrap();
+let bindings = pattern
+    .match_value(&sexpr!((define (add x y) (+ x y))))
+    .unwrap();
+
+assert_eq!(bindings.value("name"), Some(&sexpr!(add)));
+assert_eq!(bindings.values("args"), Some(vec![&sexpr!(x), &sexpr!(y)]));
+assert_eq!(bindings.values("body"), Some(vec![&sexpr!((+ x y))]));
+```
+
+ */
+
+/*
+Copyright 2024 Simon Johnston <johnstonskj@gmail.com>
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+use crate::{
+    error::{invalid_pattern, Error},
+    reader::read_str,
+    value::{Number, QuoteKind, Value},
+};
+use std::{collections::BTreeMap, str::FromStr};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A pattern to match against values.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum Pattern {
+    /// Matches any value, `_`.
+    Any,
+    /// Matches any value, of the given type if present, and binds it to the name, `?name`.
+    Variable(String, Option<ValueType>),
+    /// Matches a value equal to this one.
+    Literal(Value),
+    /// Matches a proper list whose items match these patterns.
+    List(Vec<Pattern>),
+    /// Matches a list, or dotted list, whose first items match these patterns and where the
+    /// remaining items, or the tail, match the final pattern.
+    DottedList(Vec<Pattern>, Box<Pattern>),
+    /// Matches a vector whose items match these patterns.
+    Vector(Vec<Pattern>),
+    /// Matches a quoted value of the same kind whose datum matches the pattern.
+    Quoted(QuoteKind, Box<Pattern>),
+    /// Matches zero or more items of a list or vector, `pattern ...`.
+    Repeat(Box<Pattern>),
+}
+
+///
+/// The type predicates that may be used with a pattern variable, `?name:type`.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ValueType {
+    Atom,
+    Boolean,
+    Character,
+    Integer,
+    Keyword,
+    List,
+    Map,
+    Number,
+    Pair,
+    String,
+    Symbol,
+    Vector,
+}
+
+///
+/// The value bound to a pattern variable; variables within a repeated pattern are bound to a
+/// sequence with one entry for each item matched.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum Binding {
+    Value(Value),
+    Sequence(Vec<Binding>),
+}
+
+///
+/// The variables bound by a successful match, by name.
+///
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Bindings(BTreeMap<String, Binding>);
+
+// ------------------------------------------------------------------------------------------------
+// Private Values
+// ------------------------------------------------------------------------------------------------
+
+const PATTERN_ANY: &str = "_";
+const PATTERN_VARIABLE_PREFIX: char = '?';
+const PATTERN_TYPE_SEPARATOR: char = ':';
+const PATTERN_ELLIPSIS: &str = "...";
+
+// ------------------------------------------------------------------------------------------------
+// Implementations ❱ Pattern
+// ------------------------------------------------------------------------------------------------
+
+impl FromStr for Pattern {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl TryFrom<&Value> for Pattern {
+    type Error = Error;
+
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        Self::from_value(value)
+    }
+}
+
+impl TryFrom<Value> for Pattern {
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        Self::from_value(&value)
+    }
+}
+
+impl Pattern {
+    ///
+    /// Parse a pattern from its string form.
+    ///
+    pub fn parse<S>(s: S) -> Result<Self, Error>
+    where
+        S: AsRef<str>,
+    {
+        Self::from_value(&read_str(s.as_ref())?)
+    }
+
+    ///
+    /// Build a pattern from a value, interpreting `_`, `?name`, and `...` symbols as described
+    /// in the module documentation.
+    ///
+    pub fn from_value(value: &Value) -> Result<Self, Error> {
+        match value.resolved() {
+            Value::Symbol(symbol) if !symbol.is_qualified() => {
+                let name = symbol.as_ref();
+                if name == PATTERN_ANY {
+                    Ok(Self::Any)
+                } else if name == PATTERN_ELLIPSIS {
+                    Err(invalid_pattern(
+                        "an ellipsis must follow a pattern in a list or vector",
+                    ))
+                } else if let Some(variable) = name
+                    .strip_prefix(PATTERN_VARIABLE_PREFIX)
+                    .filter(|s| !s.is_empty())
+                {
+                    match variable.split_once(PATTERN_TYPE_SEPARATOR) {
+                        Some((name, type_name)) => Ok(Self::Variable(
+                            name.to_string(),
+                            Some(ValueType::from_str(type_name)?),
+                        )),
+                        None => Ok(Self::Variable(variable.to_string(), None)),
+                    }
+                } else {
+                    Ok(Self::Literal(value.resolved().clone()))
+                }
+            }
+            Value::List(items) => Ok(Self::List(sequence_from_values(items)?)),
+            Value::Vector(items) => Ok(Self::Vector(sequence_from_values(items)?)),
+            Value::DottedList(list) => {
+                let items = sequence_from_values(list.items())?;
+                if items.iter().any(Pattern::is_repeat) {
+                    Err(invalid_pattern(
+                        "an ellipsis may not be used in a list with a rest pattern",
+                    ))
+                } else {
+                    Ok(Self::DottedList(
+                        items,
+                        Box::new(Self::from_value(list.tail())?),
+                    ))
+                }
+            }
+            Value::Quoted(quoted) => Ok(Self::Quoted(
+                quoted.kind(),
+                Box::new(Self::from_value(quoted.datum())?),
+            )),
+            value => Ok(Self::Literal(value.clone())),
+        }
+    }
+
+    pub fn variable<S>(name: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self::Variable(name.into(), None)
+    }
+
+    pub fn typed_variable<S>(name: S, value_type: ValueType) -> Self
+    where
+        S: Into<String>,
+    {
+        Self::Variable(name.into(), Some(value_type))
+    }
+
+    pub fn literal<V>(value: V) -> Self
+    where
+        V: Into<Value>,
+    {
+        Self::Literal(value.into())
+    }
+
+    pub fn repeat(pattern: Pattern) -> Self {
+        Self::Repeat(Box::new(pattern))
+    }
+
+    pub fn is_repeat(&self) -> bool {
+        matches!(self, Self::Repeat(_))
+    }
+
+    ///
+    /// Returns the names of the variables bound by this pattern, in the order they appear.
+    ///
+    pub fn variables(&self) -> Vec<&str> {
+        let mut variables = Vec::default();
+        self.collect_variables(&mut variables);
+        variables
+    }
+
+    ///
+    /// Returns `true` if `value` matches this pattern.
+    ///
+    pub fn is_match(&self, value: &Value) -> bool {
+        self.match_value(value).is_some()
+    }
+
+    ///
+    /// Match `value` against this pattern, returning the variables bound if it matches.
+    /// Comments attached to values are ignored when matching, but are kept in bound values.
+    ///
+    pub fn match_value(&self, value: &Value) -> Option<Bindings> {
+        let mut bindings = Bindings::default();
+        if self.match_into(value, &mut bindings) {
+            Some(bindings)
+        } else {
+            None
+        }
+    }
+
+    fn match_into(&self, value: &Value, bindings: &mut Bindings) -> bool {
+        let resolved = value.resolved();
+        match (self, resolved) {
+            (Self::Any, _) => true,
+            (Self::Variable(name, value_type), _) => {
+                value_type
+                    .map(|value_type| value_type.is_match(resolved))
+                    .unwrap_or(true)
+                    && bindings.bind(name, Binding::Value(value.clone()))
+            }
+            (Self::Literal(literal), _) => literal.is_equal(resolved),
+            (Self::List(patterns), Value::List(items))
+            | (Self::Vector(patterns), Value::Vector(items)) => {
+                match_sequence(patterns, items, bindings)
+            }
+            (Self::DottedList(patterns, tail), Value::List(items))
+                if items.len() >= patterns.len() =>
+            {
+                let (head, rest) = items.split_at(patterns.len());
+                match_sequence(patterns, head, bindings)
+                    && tail.match_into(&Value::List(rest.to_vec()), bindings)
+            }
+            (Self::DottedList(patterns, tail), Value::DottedList(list))
+                if list.items().len() >= patterns.len() =>
+            {
+                let (head, rest) = list.items().split_at(patterns.len());
+                match_sequence(patterns, head, bindings)
+                    && tail.match_into(
+                        &Value::improper_list(rest.to_vec(), list.tail().clone()),
+                        bindings,
+                    )
+            }
+            (Self::Quoted(kind, pattern), Value::Quoted(quoted)) => {
+                *kind == quoted.kind() && pattern.match_into(quoted.datum(), bindings)
+            }
+            _ => false,
+        }
+    }
+
+    fn collect_variables<'a>(&'a self, variables: &mut Vec<&'a str>) {
+        match self {
+            Self::Variable(name, _)
+                if name != PATTERN_ANY && !variables.contains(&name.as_str()) =>
+            {
+                variables.push(name)
+            }
+            Self::List(patterns) | Self::Vector(patterns) => patterns
+                .iter()
+                .for_each(|pattern| pattern.collect_variables(variables)),
+            Self::DottedList(patterns, tail) => {
+                patterns
+                    .iter()
+                    .for_each(|pattern| pattern.collect_variables(variables));
+                tail.collect_variables(variables);
+            }
+            Self::Quoted(_, pattern) | Self::Repeat(pattern) => {
+                pattern.collect_variables(variables)
+            }
+            _ => {}
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations ❱ ValueType
+// ------------------------------------------------------------------------------------------------
+
+impl FromStr for ValueType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "atom" => Ok(Self::Atom),
+            "boolean" | "bool" => Ok(Self::Boolean),
+            "character" | "char" => Ok(Self::Character),
+            "integer" => Ok(Self::Integer),
+            "keyword" => Ok(Self::Keyword),
+            "list" => Ok(Self::List),
+            "map" => Ok(Self::Map),
+            "number" => Ok(Self::Number),
+            "pair" => Ok(Self::Pair),
+            "string" => Ok(Self::String),
+            "symbol" => Ok(Self::Symbol),
+            "vector" => Ok(Self::Vector),
+            _ => Err(invalid_pattern(format!("unknown type '{s}' for variable"))),
+        }
+    }
+}
+
+impl ValueType {
+    ///
+    /// Returns `true` if `value` is of this type. A pair is a dotted list or a non-empty list,
+    /// and an atom is anything that is not a pair.
+    ///
+    pub fn is_match(&self, value: &Value) -> bool {
+        let value = value.resolved();
+        match self {
+            Self::Atom => !Self::Pair.is_match(value),
+            Self::Boolean => value.is_bool(),
+            Self::Character => value.is_character(),
+            #[cfg(feature = "bignum")]
+            Self::Integer => matches!(
+                value.as_number(),
+                Some(Number::Integer(_) | Number::BigInteger(_))
+            ),
+            #[cfg(not(feature = "bignum"))]
+            Self::Integer => matches!(value.as_number(), Some(Number::Integer(_))),
+            Self::Keyword => value.is_keyword(),
+            Self::List => value.is_list(),
+            Self::Map => value.is_map(),
+            Self::Number => value.is_number(),
+            Self::Pair => value.is_dotted_list() || (value.is_list() && !value.is_empty_list()),
+            Self::String => value.is_string(),
+            Self::Symbol => value.is_symbol(),
+            Self::Vector => value.is_vector(),
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations ❱ Binding
+// ------------------------------------------------------------------------------------------------
+
+impl From<Binding> for Value {
+    fn from(value: Binding) -> Self {
+        match value {
+            Binding::Value(value) => value,
+            Binding::Sequence(bindings) => bindings.into_iter().map(Value::from).collect(),
+        }
+    }
+}
+
+impl From<&Binding> for Value {
+    fn from(value: &Binding) -> Self {
+        value.clone().into()
+    }
+}
+
+impl Binding {
+    pub fn as_value(&self) -> Option<&Value> {
+        match self {
+            Self::Value(value) => Some(value),
+            Self::Sequence(_) => None,
+        }
+    }
+
+    pub fn as_sequence(&self) -> Option<&Vec<Binding>> {
+        match self {
+            Self::Value(_) => None,
+            Self::Sequence(bindings) => Some(bindings),
+        }
+    }
+
+    ///
+    /// Returns all the values in this binding, flattening any nested sequences.
+    ///
+    pub fn values(&self) -> Vec<&Value> {
+        match self {
+            Self::Value(value) => vec![value],
+            Self::Sequence(bindings) => bindings.iter().flat_map(Binding::values).collect(),
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations ❱ Bindings
+// ------------------------------------------------------------------------------------------------
+
+impl IntoIterator for Bindings {
+    type Item = (String, Binding);
+    type IntoIter = std::collections::btree_map::IntoIter<String, Binding>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl Bindings {
+    pub fn get(&self, name: &str) -> Option<&Binding> {
+        self.0.get(name)
+    }
+
+    ///
+    /// Returns the value bound to `name`, if it is bound to a single value.
+    ///
+    pub fn value(&self, name: &str) -> Option<&Value> {
+        self.get(name).and_then(Binding::as_value)
+    }
+
+    ///
+    /// Returns the values bound to `name`, flattening any sequences.
+    ///
+    pub fn values(&self, name: &str) -> Option<Vec<&Value>> {
+        self.get(name).map(Binding::values)
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.0.contains_key(name)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Binding)> {
+        self.0.iter()
+    }
+
+    fn bind(&mut self, name: &str, binding: Binding) -> bool {
+        if name == PATTERN_ANY {
+            true
+        } else if let Some(existing) = self.0.get(name) {
+            bindings_equal(existing, &binding)
+        } else {
+            let _ = self.0.insert(name.to_string(), binding);
+            true
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn sequence_from_values(values: &[Value]) -> Result<Vec<Pattern>, Error> {
+    let mut patterns = Vec::default();
+    for value in values {
+        if matches!(value.resolved().as_symbol(), Some(symbol) if symbol.as_ref() == PATTERN_ELLIPSIS)
+        {
+            match patterns.pop() {
+                Some(Pattern::Repeat(_)) | None => {
+                    return Err(invalid_pattern(
+                        "an ellipsis must follow a pattern in a list or vector",
+                    ))
+                }
+                Some(pattern) => patterns.push(Pattern::repeat(pattern)),
+            }
+        } else {
+            patterns.push(Pattern::from_value(value)?);
+        }
+    }
+    if patterns
+        .iter()
+        .filter(|pattern| pattern.is_repeat())
+        .count()
+        > 1
+    {
+        Err(invalid_pattern(
+            "only one ellipsis may be used in a list or vector",
+        ))
+    } else {
+        Ok(patterns)
+    }
+}
+
+fn match_sequence(patterns: &[Pattern], items: &[Value], bindings: &mut Bindings) -> bool {
+    let match_all = |patterns: &[Pattern], items: &[Value], bindings: &mut Bindings| {
+        patterns
+            .iter()
+            .zip(items)
+            .all(|(pattern, item)| pattern.match_into(item, bindings))
+    };
+    match patterns.iter().position(Pattern::is_repeat) {
+        None => patterns.len() == items.len() && match_all(patterns, items, bindings),
+        Some(index) => {
+            let Pattern::Repeat(repeated) = &patterns[index] else {
+                unreachable!()
+            };
+            let (before, after) = (&patterns[..index], &patterns[index + 1..]);
+            if items.len() < before.len() + after.len() {
+                return false;
+            }
+            let (head, rest) = items.split_at(before.len());
+            let (middle, tail) = rest.split_at(rest.len() - after.len());
+            if !(match_all(before, head, bindings) && match_all(after, tail, bindings)) {
+                return false;
+            }
+            let mut matched = Vec::with_capacity(middle.len());
+            for item in middle {
+                let mut item_bindings = Bindings::default();
+                if !repeated.match_into(item, &mut item_bindings) {
+                    return false;
+                }
+                matched.push(item_bindings);
+            }
+            repeated.variables().into_iter().all(|name| {
+                let sequence = matched
+                    .iter_mut()
+                    .filter_map(|item_bindings| item_bindings.0.remove(name))
+                    .collect();
+                bindings.bind(name, Binding::Sequence(sequence))
+            })
+        }
+    }
+}
+
+fn bindings_equal(lhs: &Binding, rhs: &Binding) -> bool {
+    match (lhs, rhs) {
+        (Binding::Value(lhs), Binding::Value(rhs)) => lhs.is_equal(rhs),
+        (Binding::Sequence(lhs), Binding::Sequence(rhs)) => {
+            lhs.len() == rhs.len()
+                && lhs
+                    .iter()
+                    .zip(rhs)
+                    .all(|(lhs, rhs)| bindings_equal(lhs, rhs))
+        }
+        _ => false,
+    }
+}
@@ -0,0 +1,313 @@
+/*!
+Provides a small reader for the s-expression syntax used to write patterns and templates; it
+is not intended to read the full syntax of any particular language style.
+
+The reader accepts lists, including dotted lists, vectors as `[...]` or `#(...)`, strings,
+characters as `#\c`, `#\space`, `#\newline`, or `#\tab`, booleans as `#t`, `#f`, `#true`, or
+`#false`, integers, rationals as `n/d`, floating point numbers, keywords as `:name`,
+uninterned symbols as `#:name`, as written for Common Lisp and Emacs Lisp, the quote forms `'`,
+`` ` ``, `,`, and `,@`, and symbols. Line comments start with `;`.
+
+Integers that do not fit in an `i64` are read as big integers with the `bignum` feature, and are
+an error without it; both parts of a rational must fit in an `i64`.
+
+ */
+
+/*
+Copyright 2024 Simon Johnston <johnstonskj@gmail.com>
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[cfg(feature = "bignum")]
+use crate::value::Number;
+use crate::{
+    error::{parser_error, Error},
+    value::{Keyword, QuoteKind, Quoted, Rational, Symbol, Value},
+};
+#[cfg(feature = "bignum")]
+use num_bigint::BigInt;
+use std::{iter::Peekable, str::CharIndices};
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Read exactly one datum from `s`, returning an error if there is no datum, or anything other
+/// than whitespace and comments follows it.
+///
+pub(crate) fn read_str(s: &str) -> Result<Value, Error> {
+    let mut reader = Reader {
+        source: s,
+        chars: s.char_indices().peekable(),
+    };
+    let value = reader.read()?;
+    reader.skip_whitespace();
+    match reader.chars.peek() {
+        None => Ok(value),
+        Some((index, _)) => Err(parser_error(format!(
+            "unexpected input after datum at offset {index}"
+        ))),
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+struct Reader<'a> {
+    source: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Values
+// ------------------------------------------------------------------------------------------------
+
+const STR_DOT: &str = ".";
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl<'a> Reader<'a> {
+    fn read(&mut self) -> Result<Value, Error> {
+        self.skip_whitespace();
+        let Some((index, c)) = self.chars.next() else {
+            return Err(parser_error("unexpected end of input, expecting a datum"));
+        };
+        match c {
+            '(' => self.read_list(')'),
+            '[' => self.read_sequence(']').map(Value::Vector),
+            ')' | ']' => Err(parser_error(format!("unexpected '{c}' at offset {index}"))),
+            '"' => self.read_string(),
+            '\'' => self.read_quoted(QuoteKind::Quote),
+            '`' => self.read_quoted(QuoteKind::QuasiQuote),
+            ',' => {
+                if self.chars.next_if(|(_, c)| *c == '@').is_some() {
+                    self.read_quoted(QuoteKind::UnquoteSplicing)
+                } else {
+                    self.read_quoted(QuoteKind::Unquote)
+                }
+            }
+            '#' => self.read_hash(index),
+            _ => self.read_atom(index),
+        }
+    }
+
+    fn read_list(&mut self, close: char) -> Result<Value, Error> {
+        let mut items = Vec::default();
+        loop {
+            self.skip_whitespace();
+            match self.chars.peek().copied() {
+                None => return Err(parser_error(format!("missing '{close}' to end list"))),
+                Some((_, c)) if c == close => {
+                    let _ = self.chars.next();
+                    return Ok(Value::List(items));
+                }
+                Some((index, _)) if self.token_at(index) == STR_DOT && !items.is_empty() => {
+                    let _ = self.chars.next();
+                    let tail = self.read()?;
+                    self.skip_whitespace();
+                    return match self.chars.next() {
+                        Some((_, c)) if c == close => Ok(Value::improper_list(items, tail)),
+                        _ => Err(parser_error(format!(
+                            "expecting '{close}' after the tail of a dotted list"
+                        ))),
+                    };
+                }
+                Some(_) => items.push(self.read()?),
+            }
+        }
+    }
+
+    fn read_sequence(&mut self, close: char) -> Result<Vec<Value>, Error> {
+        let mut items = Vec::default();
+        loop {
+            self.skip_whitespace();
+            match self.chars.peek() {
+                None => return Err(parser_error(format!("missing '{close}' to end vector"))),
+                Some((_, c)) if *c == close => {
+                    let _ = self.chars.next();
+                    return Ok(items);
+                }
+                Some(_) => items.push(self.read()?),
+            }
+        }
+    }
+
+    fn read_string(&mut self) -> Result<Value, Error> {
+        let mut string = String::default();
+        while let Some((_, c)) = self.chars.next() {
+            match c {
+                '"' => return Ok(Value::String(string)),
+                '\\' => match self.chars.next() {
+                    Some((_, 'n')) => string.push('\n'),
+                    Some((_, 't')) => string.push('\t'),
+                    Some((_, 'r')) => string.push('\r'),
+                    Some((_, c)) => string.push(c),
+                    None => break,
+                },
+                c => string.push(c),
+            }
+        }
+        Err(parser_error("missing '\"' to end string"))
+    }
+
+    fn read_quoted(&mut self, kind: QuoteKind) -> Result<Value, Error> {
+        Ok(Quoted::new(kind, self.read()?).into())
+    }
+
+    fn read_hash(&mut self, index: usize) -> Result<Value, Error> {
+        match self.chars.peek() {
+            Some((_, '(')) => {
+                let _ = self.chars.next();
+                self.read_sequence(')').map(Value::Vector)
+            }
+            Some((_, '\\')) => {
+                let _ = self.chars.next();
+                let Some((start, c)) = self.chars.next() else {
+                    return Err(parser_error("unexpected end of input in character"));
+                };
+                let name = self.token_at(start);
+                if name.chars().count() > 1 {
+                    self.skip_token();
+                }
+                match name {
+                    "space" => Ok(Value::Character(' ')),
+                    "newline" => Ok(Value::Character('\n')),
+                    "tab" => Ok(Value::Character('\t')),
+                    _ if name.chars().count() <= 1 => Ok(Value::Character(c)),
+                    _ => Err(parser_error(format!(
+                        "unknown character name '{name}' at offset {index}"
+                    ))),
+                }
+            }
+            Some((_, ':')) => {
+                let token = self.token_at(index + 2);
+                self.skip_token();
+                if token.is_empty() {
+                    Err(parser_error(format!("empty symbol name at offset {index}")))
+                } else {
+                    Ok(Symbol::uninterned(token).into())
+                }
+            }
+            _ => {
+                let token = self.token_at(index + 1);
+                self.skip_token();
+                match token {
+                    "t" | "true" => Ok(Value::Bool(true)),
+                    "f" | "false" => Ok(Value::Bool(false)),
+                    _ => Err(parser_error(format!(
+                        "unknown syntax '#{token}' at offset {index}"
+                    ))),
+                }
+            }
+        }
+    }
+
+    fn read_atom(&mut self, index: usize) -> Result<Value, Error> {
+        let token = self.token_at(index);
+        self.skip_token();
+        if let Some(keyword) = token.strip_prefix(':').filter(|s| !s.is_empty()) {
+            Ok(Keyword::new(keyword).into())
+        } else if is_integer(token) {
+            read_integer(token)
+        } else if let Some((numerator, denominator)) = token.split_once('/').filter(|(n, d)| {
+            is_integer(n) && d.chars().all(|c| c.is_ascii_digit()) && !d.is_empty()
+        }) {
+            let rational = Rational::try_new(
+                read_integer(numerator)?.as_integer().ok_or_else(|| {
+                    parser_error(format!("the numerator of {token} does not fit in an i64"))
+                })?,
+                read_integer(denominator)?.as_integer().ok_or_else(|| {
+                    parser_error(format!("the denominator of {token} does not fit in an i64"))
+                })?,
+            )?;
+            Ok(if rational.is_integer() {
+                rational.numerator().into()
+            } else {
+                rational.into()
+            })
+        } else if token.contains(|c: char| c.is_ascii_digit()) {
+            if let Ok(float) = token.parse::<f64>() {
+                Ok(float.into())
+            } else {
+                Ok(Symbol::new(token).into())
+            }
+        } else {
+            Ok(Symbol::new(token).into())
+        }
+    }
+
+    fn token_at(&self, index: usize) -> &'a str {
+        let rest = &self.source[index..];
+        &rest[..rest.find(is_delimiter).unwrap_or(rest.len())]
+    }
+
+    fn skip_token(&mut self) {
+        while self.chars.next_if(|(_, c)| !is_delimiter(*c)).is_some() {}
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some((_, c)) = self.chars.peek() {
+            if c.is_whitespace() {
+                let _ = self.chars.next();
+            } else if *c == ';' {
+                while self.chars.next_if(|(_, c)| *c != '\n').is_some() {}
+            } else {
+                break;
+            }
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// An integer is an optional sign followed by one or more decimal digits.
+///
+fn is_integer(token: &str) -> bool {
+    let digits = token.strip_prefix(['+', '-']).unwrap_or(token);
+    !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit())
+}
+
+///
+/// Read an integer, as a big integer if it does not fit in an `i64` and the `bignum` feature is
+/// enabled, or return an error otherwise.
+///
+fn read_integer(token: &str) -> Result<Value, Error> {
+    if let Ok(integer) = token.parse::<i64>() {
+        return Ok(integer.into());
+    }
+    #[cfg(feature = "bignum")]
+    {
+        token
+            .parse::<BigInt>()
+            .map(|integer| Number::BigInteger(integer).into())
+            .map_err(|e| parser_error(format!("the integer {token} could not be read; {e}")))
+    }
+    #[cfg(not(feature = "bignum"))]
+    {
+        Err(parser_error(format!(
+            "the integer {token} does not fit in an i64, enable the bignum feature to read it"
+        )))
+    }
+}
+
+fn is_delimiter(c: char) -> bool {
+    c.is_whitespace() || matches!(c, '(' | ')' | '[' | ']' | '"' | ';' | '\'' | '`' | ',')
+}
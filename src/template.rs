@@ -0,0 +1,332 @@
+/*!
+Provides [`Template`], for building values from a quasiquote-style skeleton.
+
+A template is a value containing holes: `,name` is replaced by the value for `name`, and
+`,@name` by the items of the list or vector for `name`, spliced into the enclosing list or
+vector. Holes may appear within any value other than a shared value. A template may be written
+with, or without, a leading quasiquote; quasiquotes within the template are kept, as are the
+unquotes that belong to them.
+
+# Example
+
+```rust
+use sexpr_out::{sexpr, template::Template, Value};
+
+let template: Template = "(defun ,name (,@args) ,@body)".parse().unwrap();
+let value = template
+    .instantiate([
+        ("name", sexpr!(add)),
+        ("args", sexpr!((x y))),
+        ("body", sexpr!(((+ x y)))),
+    ])
+    .unwrap();
+
+assert_eq!(value, sexpr!((defun add (x y) (+ x y))));
+```
+
+ */
+
+/*
+Copyright 2024 Simon Johnston <johnstonskj@gmail.com>
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+use crate::{
+    error::{invalid_template, missing_template_value, Error},
+    reader::read_str,
+    value::{Commented, Map, QuoteKind, Quoted, Value},
+};
+use std::{
+    collections::{HashMap, HashSet},
+    str::FromStr,
+};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A value with `,name` and `,@name` holes to be filled by [`Template::instantiate`].
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct Template(Value);
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl FromStr for Template {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl TryFrom<Value> for Template {
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
+
+impl Template {
+    ///
+    /// Create a template from `value`, returning an error if a hole is not a symbol, or a
+    /// splicing hole is not within a list or vector.
+    ///
+    pub fn new<V>(value: V) -> Result<Self, Error>
+    where
+        V: Into<Value>,
+    {
+        let value = match value.into() {
+            Value::Quoted(quoted) if quoted.kind() == QuoteKind::QuasiQuote => {
+                quoted.datum().clone()
+            }
+            value => value,
+        };
+        check_template(&value, 0, false, false, &mut HashSet::default())?;
+        Ok(Self(value))
+    }
+
+    ///
+    /// Parse a template from its string form.
+    ///
+    pub fn parse<S>(s: S) -> Result<Self, Error>
+    where
+        S: AsRef<str>,
+    {
+        Self::new(read_str(s.as_ref())?)
+    }
+
+    ///
+    /// Returns the value of this template, without any leading quasiquote.
+    ///
+    pub fn value(&self) -> &Value {
+        &self.0
+    }
+
+    ///
+    /// Returns the names of the holes in this template, in the order they appear.
+    ///
+    pub fn holes(&self) -> Vec<&str> {
+        let mut holes = Vec::default();
+        collect_holes(&self.0, 0, &mut holes);
+        holes
+    }
+
+    ///
+    /// Create a new value from this template, filling each hole with the value of the same
+    /// name from `values`. Any pairs of names and values may be used, including a `HashMap`
+    /// or the [`Bindings`](crate::pattern::Bindings) from a pattern match. Comments on a
+    /// splicing hole are kept on the first item spliced, and are an error if nothing is spliced.
+    ///
+    pub fn instantiate<I, K, V>(&self, values: I) -> Result<Value, Error>
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<Value>,
+    {
+        let values: HashMap<String, Value> = values
+            .into_iter()
+            .map(|(k, v)| (k.into(), v.into()))
+            .collect();
+        fill(&self.0, 0, &values)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Returns the name of the hole if `value` is an unquote of `kind` at quasiquote `depth` zero.
+///
+fn hole_name(value: &Value, depth: usize, kind: QuoteKind) -> Option<&str> {
+    match value {
+        Value::Quoted(quoted) if depth == 0 && quoted.kind() == kind => {
+            quoted.datum().as_symbol().map(|symbol| symbol.as_ref())
+        }
+        _ => None,
+    }
+}
+
+fn nested_depth(quoted: &Quoted, depth: usize) -> usize {
+    match quoted.kind() {
+        QuoteKind::QuasiQuote => depth + 1,
+        QuoteKind::Unquote | QuoteKind::UnquoteSplicing => depth.saturating_sub(1),
+        _ => depth,
+    }
+}
+
+///
+/// Check the holes in `value`. Holes may appear within any value other than a shared value,
+/// which may be cyclic and is not copied by [`Template::instantiate`]; `visited` holds the
+/// shared values already checked.
+///
+fn check_template(
+    value: &Value,
+    depth: usize,
+    in_sequence: bool,
+    in_shared: bool,
+    visited: &mut HashSet<*const Value>,
+) -> Result<(), Error> {
+    let mut check = |value: &Value, depth: usize, in_sequence: bool| {
+        check_template(value, depth, in_sequence, in_shared, visited)
+    };
+    match value {
+        Value::Quoted(quoted) if depth == 0 => match quoted.kind() {
+            QuoteKind::Unquote | QuoteKind::UnquoteSplicing if in_shared => {
+                Err(invalid_template("a hole may not be within a shared value"))
+            }
+            QuoteKind::Unquote | QuoteKind::UnquoteSplicing if !quoted.datum().is_symbol() => Err(
+                invalid_template(format!("a hole must be a symbol, not {:?}", quoted.datum())),
+            ),
+            QuoteKind::UnquoteSplicing if !in_sequence => Err(invalid_template(
+                "a splicing hole must be an item of a list or vector",
+            )),
+            _ => check(quoted.datum(), nested_depth(quoted, depth), false),
+        },
+        Value::Quoted(quoted) => check(quoted.datum(), nested_depth(quoted, depth), false),
+        Value::List(items) | Value::Vector(items) => {
+            items.iter().try_for_each(|item| check(item, depth, true))
+        }
+        Value::DottedList(list) => {
+            list.items()
+                .iter()
+                .try_for_each(|item| check(item, depth, true))?;
+            check(list.tail(), depth, false)
+        }
+        Value::Commented(commented) => check(commented.value(), depth, in_sequence),
+        Value::Shared(shared) => match shared.get() {
+            Some(value) if visited.insert(value) => {
+                check_template(value, depth, false, true, visited)
+            }
+            _ => Ok(()),
+        },
+        value => value
+            .children()
+            .into_iter()
+            .try_for_each(|child| check(child, depth, false)),
+    }
+}
+
+fn collect_holes<'a>(value: &'a Value, depth: usize, holes: &mut Vec<&'a str>) {
+    if let Some(name) = hole_name(value, depth, QuoteKind::Unquote)
+        .or_else(|| hole_name(value, depth, QuoteKind::UnquoteSplicing))
+    {
+        if !holes.contains(&name) {
+            holes.push(name);
+        }
+        return;
+    }
+    match value {
+        Value::Quoted(quoted) => collect_holes(quoted.datum(), nested_depth(quoted, depth), holes),
+        value => value
+            .children()
+            .into_iter()
+            .for_each(|child| collect_holes(child, depth, holes)),
+    }
+}
+
+fn lookup<'a>(name: &str, values: &'a HashMap<String, Value>) -> Result<&'a Value, Error> {
+    values.get(name).ok_or_else(|| missing_template_value(name))
+}
+
+fn fill(value: &Value, depth: usize, values: &HashMap<String, Value>) -> Result<Value, Error> {
+    if let Some(name) = hole_name(value, depth, QuoteKind::Unquote) {
+        return lookup(name, values).cloned();
+    }
+    match value {
+        Value::Quoted(quoted) => Ok(Quoted::new(
+            quoted.kind(),
+            fill(quoted.datum(), nested_depth(quoted, depth), values)?,
+        )
+        .into()),
+        Value::List(items) => Ok(Value::List(fill_sequence(items, depth, values)?)),
+        Value::Vector(items) => Ok(Value::Vector(fill_sequence(items, depth, values)?)),
+        Value::DottedList(list) => {
+            // a splice of an empty list leaves only the tail.
+            Ok(Value::improper_list(
+                fill_sequence(list.items(), depth, values)?,
+                fill(list.tail(), depth, values)?,
+            ))
+        }
+        Value::Commented(commented) => {
+            let mut filled: Commented = commented.clone();
+            filled.set_value(fill(commented.value(), depth, values)?);
+            Ok(filled.into())
+        }
+        // shared values are not copied, check_template ensures they contain no holes.
+        Value::Shared(_) => Ok(value.clone()),
+        Value::Map(map) => {
+            // rebuilt through insert so that keys filled with equal values are not duplicated.
+            let mut filled = Map::new().with_test(*map.test());
+            for (key, value) in map.entries() {
+                filled.insert(fill(key, depth, values)?, fill(value, depth, values)?);
+            }
+            Ok(filled.into())
+        }
+        value => {
+            let mut filled = value.clone();
+            let children = filled
+                .take_children()
+                .iter()
+                .map(|child| fill(child, depth, values))
+                .collect::<Result<Vec<Value>, Error>>()?;
+            filled.restore_children(children);
+            Ok(filled)
+        }
+    }
+}
+
+fn fill_sequence(
+    items: &[Value],
+    depth: usize,
+    values: &HashMap<String, Value>,
+) -> Result<Vec<Value>, Error> {
+    let mut filled = Vec::with_capacity(items.len());
+    for item in items {
+        match hole_name(item.resolved(), depth, QuoteKind::UnquoteSplicing) {
+            Some(name) => {
+                let spliced = match lookup(name, values)?.resolved() {
+                    Value::List(spliced) | Value::Vector(spliced) => spliced,
+                    _ => {
+                        return Err(invalid_template(format!(
+                            "the value for the splicing hole ,@{name} is not a list or vector"
+                        )))
+                    }
+                };
+                match (item, spliced.split_first()) {
+                    // the comments on a splicing hole are kept on the first item spliced.
+                    (Value::Commented(commented), Some((first, rest))) => {
+                        let mut first_filled: Commented = commented.clone();
+                        first_filled.set_value(first.clone());
+                        filled.push(first_filled.into());
+                        filled.extend(rest.iter().cloned());
+                    }
+                    (Value::Commented(_), None) => {
+                        return Err(invalid_template(format!(
+                            "the comments on the splicing hole ,@{name} have no item to be kept on"
+                        )))
+                    }
+                    _ => filled.extend(spliced.iter().cloned()),
+                }
+            }
+            None => filled.push(fill(item, depth, values)?),
+        }
+    }
+    Ok(filled)
+}
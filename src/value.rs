@@ -1,6 +1,10 @@
 /*!
 This module provides the common [`Value`] enum for atomic values (bool, integer, float, character,
-string, symbol, and keyword) and lists of values.
+string, symbol, and keyword), byte strings, lists and vectors of values, dotted (improper) lists,
+maps, quoted values, and shared values with datum labels. It also includes values that only some
+styles can represent such as boxes, prefab structures and records, regular expressions, paths, bit
+vectors, multi-dimensional arrays, characters with modifiers, strings with text properties, and
+tree-sitter nodes.
 
 Similar in approach to crates such as `serde_json` the [`Value`] enum is the representation of data
 supported directly by the representation, in this case Lisp-like s-expressions. Values are
@@ -24,23 +28,322 @@ See the License for the specific language governing permissions and
 limitations under the License.
 */
 
+use crate::{
+    error::{invalid_value, symbol_parser_error, Error},
+    writer::LanguageStyle,
+};
+#[cfg(feature = "bignum")]
+use num_bigint::{BigInt, BigUint};
+use std::{
+    cmp::Ordering,
+    collections::HashSet,
+    fmt::Debug,
+    hash::{Hash, Hasher},
+    ops::{Index, IndexMut},
+    path::{Path, PathBuf},
+    sync::{Arc, OnceLock},
+};
+#[cfg(feature = "normalization")]
+use unicode_normalization::UnicodeNormalization;
+
 // ------------------------------------------------------------------------------------------------
 // Public Types
 // ------------------------------------------------------------------------------------------------
 
-#[derive(Clone, Copy, Debug)]
+///
+/// Numbers are integers, exact rationals, floating point, or complex values. With the `bignum`
+/// feature enabled integers that do not fit into an `i64` are held as arbitrary-precision values
+/// and are written exactly.
+///
+/// Integers are compared, and hashed, by value whether they are held as an `i64` or as an
+/// arbitrary-precision value, so `Integer(5)` and `BigInteger(5)` are equal. Otherwise numbers
+/// of different kinds are never equal, and are ordered by kind: integers, rationals, floating
+/// point values, and then complex numbers; so `1` and `1.0` are not equal and `1` is less than
+/// `0.5`. Numbers of the same kind are ordered numerically, with floating point values using the
+/// IEEE 754 total order where `-0.0` is less than `0.0`, and a positive NaN is greater than
+/// infinity and equal to itself.
+///
+#[derive(Clone, Debug)]
 pub enum Number {
     Integer(i64),
+    #[cfg(feature = "bignum")]
+    BigInteger(BigInt),
+    Rational(Rational),
     Flonum(f64),
+    Complex(Complex),
 }
 
-#[derive(Clone, Debug)]
-pub struct Symbol(String);
+///
+/// An exact rational number, always held in lowest terms with a positive denominator.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Rational {
+    numerator: i64,
+    denominator: i64,
+}
 
-#[derive(Clone, Debug)]
+///
+/// A symbol, with an optional qualifier naming the package or namespace it belongs to.
+///
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Symbol {
+    qualifier: Option<SymbolQualifier>,
+    name: String,
+}
+
+///
+/// The qualifier of a [`Symbol`]. Common Lisp symbols may be external, `pkg:sym`, or internal,
+/// `pkg::sym`, to a package, and Common Lisp and Emacs Lisp both support uninterned symbols,
+/// `#:sym`. Namespaced symbols are written as `ns/name` in Racket, Scheme, and Emacs Lisp, where
+/// `/` is an ordinary symbol character conventionally used to separate a namespace; Common Lisp
+/// has packages instead and tree-sitter has no qualified names, so neither supports them.
+///
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum SymbolQualifier {
+    External(String),
+    Internal(String),
+    Uninterned,
+    Namespace(String),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Keyword(Symbol);
 
-#[derive(Clone, Debug)]
+///
+/// A dotted, or improper, list such as `(a . b)` or `(1 2 . 3)`. The list has at least one item
+/// before the dot and a single tail value after it.
+///
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct DottedList {
+    items: Vec<Value>,
+    tail: Box<Value>,
+}
+
+///
+/// The test used to compare keys in a [`Map`]; this determines the literal form written for
+/// Racket (`#hash`, `#hasheqv`, or `#hasheq`) and the `test` property for Emacs Lisp.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum MapTest {
+    #[default]
+    Equal,
+    Eqv,
+    Eq,
+}
+
+///
+/// A map, or hash table, of key/value pairs. Entries are kept in insertion order so that the
+/// written form is stable.
+///
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Map {
+    test: MapTest,
+    entries: Vec<(Value, Value)>,
+}
+
+///
+/// The quote-family reader forms. `Function` is the function designator, `#'name`, supported by
+/// Common Lisp and Emacs Lisp.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum QuoteKind {
+    Quote,
+    QuasiQuote,
+    Unquote,
+    UnquoteSplicing,
+    Function,
+}
+
+///
+/// A datum wrapped in one of the quote-family forms, written either in the short form `'x` or
+/// the long form `(quote x)` depending on [`crate::writer::QuoteStyle::is_long_form`].
+///
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Quoted {
+    kind: QuoteKind,
+    datum: Box<Value>,
+}
+
+///
+/// A prefab structure in Racket, or a record in Emacs Lisp, written as `#s(name field ...)`.
+///
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Struct {
+    name: Symbol,
+    fields: Vec<Value>,
+}
+
+///
+/// A multi-dimensional array with elements held in row-major order, written as
+/// `#2A((1 2) (3 4))` in Common Lisp.
+///
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Array {
+    dimensions: Vec<usize>,
+    elements: Vec<Value>,
+}
+
+///
+/// A modifier key applied to a character, as used for Emacs Lisp key sequences.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum CharModifier {
+    Alt,
+    Control,
+    Hyper,
+    Meta,
+    Shift,
+    Super,
+}
+
+///
+/// A character with modifier keys applied, written as `?\C-a` or `?\C-\M-x` in Emacs Lisp.
+///
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ModifiedChar {
+    modifiers: Vec<CharModifier>,
+    character: char,
+}
+
+///
+/// A string with text properties, each applying to the characters from `start` (inclusive) to
+/// `end` (exclusive), written as `#("text" 0 4 (face bold))` in Emacs Lisp.
+///
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PropertizedString {
+    text: String,
+    properties: Vec<TextProperties>,
+}
+
+///
+/// The property list applied to a range of characters in a [`PropertizedString`].
+///
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TextProperties {
+    start: usize,
+    end: usize,
+    properties: Vec<(Symbol, Value)>,
+}
+
+///
+/// A node in a tree-sitter syntax tree, written in the same form as the output of the
+/// `tree-sitter parse` command, or as expected in a tree-sitter test corpus. A node may be labeled
+/// with the name of the parent's field that contains it and with the range of source it spans.
+///
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Node {
+    kind: NodeKind,
+    field: Option<String>,
+    range: Option<(Point, Point)>,
+    children: Vec<Node>,
+}
+
+///
+/// The kind of a tree-sitter node; named nodes are written as `(kind ...)`, anonymous nodes as
+/// `"text"`, missing nodes, inserted by error recovery, as `(MISSING kind)` or
+/// `(MISSING "text")`, and error nodes as `(ERROR ...)`.
+///
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum NodeKind {
+    Named(String),
+    Anonymous(String),
+    Missing { kind: String, named: bool },
+    Error,
+}
+
+///
+/// A zero-based row and column position in source text.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point {
+    row: usize,
+    column: usize,
+}
+
+///
+/// The syntax of a regular expression literal; either `regexp` syntax, written as `#rx"..."`,
+/// or Perl-compatible `pregexp` syntax, written as `#px"..."`.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum RegexpSyntax {
+    #[default]
+    Regexp,
+    Perl,
+}
+
+///
+/// A regular expression literal. A byte regexp matches byte strings and is written with a byte
+/// string pattern, as `#rx#"..."`.
+///
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Regexp {
+    syntax: RegexpSyntax,
+    byte_regexp: bool,
+    pattern: String,
+}
+
+///
+/// A value that may be referenced from more than one place in a tree, or from within itself.
+/// When written, a shared value that is referenced more than once is written in full the first
+/// time, labeled as `#0=value`, and later references are written as `#0#`.
+///
+/// A cyclic structure is built by creating an empty shared value, using clones of it within the
+/// value, and then setting it. Note that such a cycle is never dropped.
+///
+#[derive(Clone)]
+pub struct Shared(Arc<OnceLock<Value>>);
+
+///
+/// An explicit datum label, either defining the label for a value, `#0=value`, or referencing a
+/// previously labeled value, `#0#`.
+///
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum DatumLabel {
+    Define(usize, Box<Value>),
+    Reference(usize),
+}
+
+///
+/// The number of semicolons used for a line comment; by convention `;` is used for margin
+/// comments following code, `;;` for comments on their own line within code, and `;;;` for
+/// top-level or section comments.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum LineCommentLevel {
+    #[default]
+    Margin,
+    Code,
+    Section,
+}
+
+///
+/// A comment attached to a value, either a line comment (`; text`), a block comment
+/// (`#| text |#`), or a datum comment (`#;datum`).
+///
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Comment {
+    Line(LineCommentLevel, String),
+    Block(String),
+    Datum(Box<Value>),
+}
+
+///
+/// A value with comments attached. Leading comments are written on their own lines before the
+/// value and an optional trailing comment is written on the same line after it.
+///
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Commented {
+    leading: Vec<Comment>,
+    value: Box<Value>,
+    trailing: Option<Comment>,
+}
+
+///
+/// Values are compared structurally, and ordered by variant and then by content. Maps compare
+/// their entries in insertion order and shared values are compared by identity, not by content;
+/// see [`Value::is_equal`] for a Lisp-style structural comparison.
+///
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum Value {
     Bool(bool),
     Number(Number),
@@ -49,6 +352,44 @@ pub enum Value {
     Symbol(Symbol),
     Keyword(Keyword),
     List(Vec<Value>),
+    DottedList(DottedList),
+    Vector(Vec<Value>),
+    Map(Map),
+    Bytes(Vec<u8>),
+    Quoted(Quoted),
+    Commented(Commented),
+    Boxed(Box<Value>),
+    Struct(Struct),
+    Regexp(Regexp),
+    Path(PathBuf),
+    BitVector(Vec<bool>),
+    Array(Array),
+    ModifiedChar(ModifiedChar),
+    PropertizedString(PropertizedString),
+    Node(Node),
+    Shared(Shared),
+    DatumLabel(DatumLabel),
+}
+
+///
+/// A complex number in rectangular form.
+///
+#[derive(Clone, Copy, Debug)]
+pub struct Complex {
+    real: f64,
+    imaginary: f64,
+}
+
+///
+/// One step in a path into a value, as used by [`Value::get_in`] and the related editing
+/// functions. A step selects an item of a list or vector by position, the value for a key in a
+/// map or association list, or the value following a keyword in a property list.
+///
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Selector {
+    Index(usize),
+    Key(Value),
+    Property(Keyword),
 }
 
 // ------------------------------------------------------------------------------------------------
@@ -103,6 +444,103 @@ impl From<&i8> for Number {
     }
 }
 
+impl From<u32> for Number {
+    fn from(value: u32) -> Self {
+        Self::Integer(value as i64)
+    }
+}
+
+impl From<&u32> for Number {
+    fn from(value: &u32) -> Self {
+        Self::Integer(*value as i64)
+    }
+}
+
+impl From<u16> for Number {
+    fn from(value: u16) -> Self {
+        Self::Integer(value as i64)
+    }
+}
+
+impl From<&u16> for Number {
+    fn from(value: &u16) -> Self {
+        Self::Integer(*value as i64)
+    }
+}
+
+impl From<u8> for Number {
+    fn from(value: u8) -> Self {
+        Self::Integer(value as i64)
+    }
+}
+
+impl From<&u8> for Number {
+    fn from(value: &u8) -> Self {
+        Self::Integer(*value as i64)
+    }
+}
+
+#[cfg(feature = "bignum")]
+macro_rules! big_integer_from {
+    ($type:ty) => {
+        impl From<$type> for Number {
+            fn from(value: $type) -> Self {
+                i64::try_from(value)
+                    .map(Self::Integer)
+                    .unwrap_or_else(|_| Self::BigInteger(BigInt::from(value)))
+            }
+        }
+
+        impl From<&$type> for Number {
+            fn from(value: &$type) -> Self {
+                Self::from(*value)
+            }
+        }
+    };
+}
+
+#[cfg(feature = "bignum")]
+big_integer_from!(u64);
+
+#[cfg(feature = "bignum")]
+big_integer_from!(usize);
+
+#[cfg(feature = "bignum")]
+big_integer_from!(i128);
+
+#[cfg(feature = "bignum")]
+big_integer_from!(u128);
+
+#[cfg(feature = "bignum")]
+impl From<BigInt> for Number {
+    fn from(value: BigInt) -> Self {
+        i64::try_from(&value)
+            .map(Self::Integer)
+            .unwrap_or(Self::BigInteger(value))
+    }
+}
+
+#[cfg(feature = "bignum")]
+impl From<&BigInt> for Number {
+    fn from(value: &BigInt) -> Self {
+        Self::from(value.clone())
+    }
+}
+
+#[cfg(feature = "bignum")]
+impl From<BigUint> for Number {
+    fn from(value: BigUint) -> Self {
+        Self::from(BigInt::from(value))
+    }
+}
+
+#[cfg(feature = "bignum")]
+impl From<&BigUint> for Number {
+    fn from(value: &BigUint) -> Self {
+        Self::from(BigInt::from(value.clone()))
+    }
+}
+
 impl From<f64> for Number {
     fn from(value: f64) -> Self {
         Self::Flonum(value)
@@ -127,187 +565,601 @@ impl From<&f32> for Number {
     }
 }
 
+impl From<Rational> for Number {
+    fn from(value: Rational) -> Self {
+        Self::Rational(value)
+    }
+}
+
+impl From<&Rational> for Number {
+    fn from(value: &Rational) -> Self {
+        Self::Rational(*value)
+    }
+}
+
+impl From<Complex> for Number {
+    fn from(value: Complex) -> Self {
+        Self::Complex(value)
+    }
+}
+
+impl From<&Complex> for Number {
+    fn from(value: &Complex) -> Self {
+        Self::Complex(*value)
+    }
+}
+
+impl PartialEq for Number {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Number {}
+
+impl PartialOrd for Number {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Number {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Self::Integer(lhs), Self::Integer(rhs)) => lhs.cmp(rhs),
+            #[cfg(feature = "bignum")]
+            (Self::BigInteger(lhs), Self::BigInteger(rhs)) => lhs.cmp(rhs),
+            #[cfg(feature = "bignum")]
+            (Self::Integer(lhs), Self::BigInteger(rhs)) => BigInt::from(*lhs).cmp(rhs),
+            #[cfg(feature = "bignum")]
+            (Self::BigInteger(lhs), Self::Integer(rhs)) => lhs.cmp(&BigInt::from(*rhs)),
+            (Self::Rational(lhs), Self::Rational(rhs)) => lhs.cmp(rhs),
+            (Self::Flonum(lhs), Self::Flonum(rhs)) => lhs.total_cmp(rhs),
+            (Self::Complex(lhs), Self::Complex(rhs)) => lhs.cmp(rhs),
+            _ => self.kind_order().cmp(&other.kind_order()),
+        }
+    }
+}
+
+impl Hash for Number {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.kind_order().hash(state);
+        match self {
+            Self::Integer(v) => v.hash(state),
+            // hash as the equal `Integer` if the value fits.
+            #[cfg(feature = "bignum")]
+            Self::BigInteger(v) => match i64::try_from(v) {
+                Ok(v) => v.hash(state),
+                Err(_) => v.hash(state),
+            },
+            Self::Rational(v) => v.hash(state),
+            Self::Flonum(v) => v.to_bits().hash(state),
+            Self::Complex(v) => v.hash(state),
+        }
+    }
+}
+
 impl Number {
+    fn kind_order(&self) -> u8 {
+        match self {
+            Self::Integer(_) => 0,
+            #[cfg(feature = "bignum")]
+            Self::BigInteger(_) => 0,
+            Self::Rational(_) => 1,
+            Self::Flonum(_) => 2,
+            Self::Complex(_) => 3,
+        }
+    }
+
+    ///
+    /// Returns `true` for both `i64` and, with the `bignum` feature, big integers.
+    ///
     pub fn is_integer(&self) -> bool {
-        matches!(self, Self::Integer(_))
+        match self {
+            Self::Integer(_) => true,
+            #[cfg(feature = "bignum")]
+            Self::BigInteger(_) => true,
+            _ => false,
+        }
     }
 
+    ///
+    /// Returns the value of an integer, including a big integer, that fits in an `i64`.
+    ///
     pub fn as_integer(&self) -> Option<i64> {
         match self {
             Number::Integer(v) => Some(*v),
+            #[cfg(feature = "bignum")]
+            Number::BigInteger(v) => i64::try_from(v).ok(),
             _ => None,
         }
     }
 
-    pub fn is_flonum(&self) -> bool {
-        matches!(self, Self::Flonum(_))
+    #[cfg(feature = "bignum")]
+    pub fn is_big_integer(&self) -> bool {
+        matches!(self, Self::BigInteger(_))
     }
 
-    pub fn as_flonum(&self) -> Option<f64> {
+    #[cfg(feature = "bignum")]
+    pub fn as_big_integer(&self) -> Option<BigInt> {
         match self {
-            Number::Flonum(v) => Some(*v),
+            Number::Integer(v) => Some(BigInt::from(*v)),
+            Number::BigInteger(v) => Some(v.clone()),
             _ => None,
         }
     }
-}
 
-// ------------------------------------------------------------------------------------------------
-// Implementations ❱ Symbol
-// ------------------------------------------------------------------------------------------------
+    pub fn is_rational(&self) -> bool {
+        matches!(self, Self::Rational(_))
+    }
 
-impl AsRef<str> for Symbol {
-    fn as_ref(&self) -> &str {
-        &self.0
+    pub fn as_rational(&self) -> Option<Rational> {
+        match self {
+            Number::Rational(v) => Some(*v),
+            _ => None,
+        }
     }
-}
 
-impl From<Symbol> for String {
-    fn from(value: Symbol) -> Self {
-        value.0
+    pub fn is_flonum(&self) -> bool {
+        matches!(self, Self::Flonum(_))
     }
-}
 
-impl Symbol {
-    pub fn new<S>(s: S) -> Self
-    where
-        S: Into<String>,
-    {
-        Self(s.into())
+    pub fn as_flonum(&self) -> Option<f64> {
+        match self {
+            Number::Flonum(v) => Some(*v),
+            _ => None,
+        }
     }
 
-    pub fn inner(&self) -> String {
-        self.0.clone()
+    pub fn is_complex(&self) -> bool {
+        matches!(self, Self::Complex(_))
+    }
+
+    pub fn as_complex(&self) -> Option<Complex> {
+        match self {
+            Number::Complex(v) => Some(*v),
+            _ => None,
+        }
     }
 }
 
 // ------------------------------------------------------------------------------------------------
-// Implementations ❱ Keyword
+// Implementations ❱ Rational
 // ------------------------------------------------------------------------------------------------
 
-impl From<Symbol> for Keyword {
-    fn from(value: Symbol) -> Self {
-        Self(value)
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
     }
 }
 
-impl From<Keyword> for Symbol {
-    fn from(value: Keyword) -> Self {
-        value.0
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // denominators are always positive so cross-multiplying preserves the order.
+        (self.numerator as i128 * other.denominator as i128)
+            .cmp(&(other.numerator as i128 * self.denominator as i128))
     }
 }
 
-impl From<Keyword> for String {
-    fn from(value: Keyword) -> Self {
-        value.0.into()
+impl TryFrom<(i64, i64)> for Rational {
+    type Error = Error;
+
+    fn try_from(value: (i64, i64)) -> Result<Self, Self::Error> {
+        Self::try_new(value.0, value.1)
     }
 }
 
-impl Keyword {
-    pub fn new<S>(s: S) -> Self
-    where
-        S: Into<String>,
-    {
-        Self(Symbol::new(s.into()))
+impl Rational {
+    ///
+    /// Create a rational number in lowest terms with a positive denominator.
+    ///
+    /// # Panics
+    ///
+    /// If `denominator` is zero, or the normalized value cannot be represented, as for
+    /// `i64::MIN/-1`; use [`Rational::try_new`] to handle these as errors.
+    ///
+    pub fn new(numerator: i64, denominator: i64) -> Self {
+        match Self::try_new(numerator, denominator) {
+            Ok(rational) => rational,
+            Err(e) => panic!("{}", e),
+        }
     }
 
-    pub fn inner(&self) -> Symbol {
-        self.0.clone()
+    ///
+    /// Create a rational number in lowest terms with a positive denominator, returning an
+    /// error if `denominator` is zero, or the normalized value cannot be represented.
+    ///
+    pub fn try_new(numerator: i64, denominator: i64) -> Result<Self, Error> {
+        if denominator == 0 {
+            return Err(invalid_value(format!(
+                "the rational {numerator}/{denominator} has a zero denominator"
+            )));
+        }
+        // the divisor may be 2^63, which does not fit an i64, so reduce the magnitudes first.
+        let divisor = gcd(numerator.unsigned_abs(), denominator.unsigned_abs());
+        let negative = (numerator < 0) != (denominator < 0);
+        let magnitude = numerator.unsigned_abs() / divisor;
+        let reduced_numerator = if negative {
+            0_i64.checked_sub_unsigned(magnitude)
+        } else {
+            i64::try_from(magnitude).ok()
+        };
+        let reduced_denominator = i64::try_from(denominator.unsigned_abs() / divisor).ok();
+        match (reduced_numerator, reduced_denominator) {
+            (Some(numerator), Some(denominator)) => Ok(Self {
+                numerator,
+                denominator,
+            }),
+            _ => Err(invalid_value(format!(
+                "the rational {numerator}/{denominator} cannot be represented in lowest terms"
+            ))),
+        }
     }
-}
-
-// ------------------------------------------------------------------------------------------------
-// Implementations ❱ Value::Bool
-// ------------------------------------------------------------------------------------------------
 
-impl From<bool> for Value {
-    fn from(value: bool) -> Self {
-        Self::Bool(value)
+    pub fn numerator(&self) -> i64 {
+        self.numerator
     }
-}
 
-impl From<&bool> for Value {
-    fn from(value: &bool) -> Self {
-        Self::Bool(*value)
+    pub fn denominator(&self) -> i64 {
+        self.denominator
     }
-}
 
-// ------------------------------------------------------------------------------------------------
-// Implementations ❱ Value::Number
-// ------------------------------------------------------------------------------------------------
+    pub fn is_integer(&self) -> bool {
+        self.denominator == 1
+    }
 
-impl<N: Into<Number>> From<N> for Value {
-    fn from(value: N) -> Self {
-        Self::Number(value.into())
+    pub fn to_flonum(&self) -> f64 {
+        self.numerator as f64 / self.denominator as f64
     }
 }
 
 // ------------------------------------------------------------------------------------------------
-// Implementations ❱ Value::Character
+// Implementations ❱ Complex
 // ------------------------------------------------------------------------------------------------
 
-impl From<char> for Value {
-    fn from(value: char) -> Self {
-        Self::Character(value)
+impl From<(f64, f64)> for Complex {
+    fn from(value: (f64, f64)) -> Self {
+        Self::new(value.0, value.1)
     }
 }
 
-impl From<&char> for Value {
-    fn from(value: &char) -> Self {
-        Self::Character(*value)
+impl PartialEq for Complex {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
     }
 }
 
-// ------------------------------------------------------------------------------------------------
-// Implementations ❱ Value::String
-// ------------------------------------------------------------------------------------------------
+impl Eq for Complex {}
 
-impl From<String> for Value {
-    fn from(value: String) -> Self {
-        Self::String(value)
+impl PartialOrd for Complex {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
     }
 }
 
-impl From<&String> for Value {
-    fn from(value: &String) -> Self {
-        Self::String(value.clone())
+/// Complex numbers are ordered by their real part and then their imaginary part, each using the
+/// IEEE 754 total order.
+impl Ord for Complex {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.real
+            .total_cmp(&other.real)
+            .then_with(|| self.imaginary.total_cmp(&other.imaginary))
     }
 }
 
-impl From<&str> for Value {
-    fn from(value: &str) -> Self {
-        Self::String(value.to_string())
+impl Hash for Complex {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.real.to_bits().hash(state);
+        self.imaginary.to_bits().hash(state);
     }
 }
 
-// ------------------------------------------------------------------------------------------------
-// Implementations ❱ Value::Symbol
-// ------------------------------------------------------------------------------------------------
+impl Complex {
+    pub fn new(real: f64, imaginary: f64) -> Self {
+        Self { real, imaginary }
+    }
 
-impl From<Symbol> for Value {
-    fn from(value: Symbol) -> Self {
-        Self::Symbol(value)
+    pub fn real(&self) -> f64 {
+        self.real
     }
-}
 
-impl From<&Symbol> for Value {
-    fn from(value: &Symbol) -> Self {
-        Self::Symbol(value.clone())
+    pub fn imaginary(&self) -> f64 {
+        self.imaginary
     }
 }
 
 // ------------------------------------------------------------------------------------------------
-// Implementations ❱ Value::Keyword
+// Implementations ❱ Symbol
 // ------------------------------------------------------------------------------------------------
 
-impl From<Keyword> for Value {
-    fn from(value: Keyword) -> Self {
-        Self::Keyword(value)
+impl AsRef<str> for Symbol {
+    fn as_ref(&self) -> &str {
+        &self.name
     }
 }
 
-impl From<&Keyword> for Value {
-    fn from(value: &Keyword) -> Self {
-        Self::Keyword(value.clone())
+impl From<Symbol> for String {
+    fn from(value: Symbol) -> Self {
+        value.name
+    }
+}
+
+impl Symbol {
+    pub fn new<S>(s: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            qualifier: None,
+            name: s.into(),
+        }
+    }
+
+    pub fn qualified<S>(qualifier: SymbolQualifier, name: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            qualifier: Some(qualifier),
+            name: name.into(),
+        }
+    }
+
+    pub fn external<S1, S2>(package: S1, name: S2) -> Self
+    where
+        S1: Into<String>,
+        S2: Into<String>,
+    {
+        Self::qualified(SymbolQualifier::External(package.into()), name)
+    }
+
+    pub fn internal<S1, S2>(package: S1, name: S2) -> Self
+    where
+        S1: Into<String>,
+        S2: Into<String>,
+    {
+        Self::qualified(SymbolQualifier::Internal(package.into()), name)
+    }
+
+    pub fn uninterned<S>(name: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self::qualified(SymbolQualifier::Uninterned, name)
+    }
+
+    pub fn namespaced<S1, S2>(namespace: S1, name: S2) -> Self
+    where
+        S1: Into<String>,
+        S2: Into<String>,
+    {
+        Self::qualified(SymbolQualifier::Namespace(namespace.into()), name)
+    }
+
+    ///
+    /// Parse a symbol, returning an error if `s` is not a valid identifier in `style`, or would
+    /// need escaping to be written. In Common Lisp the package-qualified forms `pkg:sym`,
+    /// `pkg::sym`, and `#:sym` are parsed into qualified symbols, as is `#:sym` in Emacs Lisp.
+    ///
+    pub fn parse<S>(s: S, style: LanguageStyle) -> Result<Self, Error>
+    where
+        S: AsRef<str>,
+    {
+        let s = s.as_ref();
+        let uninterned = s
+            .strip_prefix(SYMBOL_UNINTERNED_PREFIX)
+            .filter(|_| matches!(style, LanguageStyle::CommonLisp | LanguageStyle::EmacsLisp));
+        let symbol = match (uninterned, style) {
+            (Some(name), _) => Self::uninterned(name),
+            (None, LanguageStyle::CommonLisp) if s.starts_with(SYMBOL_PACKAGE_MARKER) => {
+                return Err(symbol_parser_error(format!(
+                    "{s:?} would be read as a keyword in {style:?}"
+                )))
+            }
+            (None, LanguageStyle::CommonLisp) => {
+                if let Some((package, name)) = s.split_once(SYMBOL_INTERNAL_MARKER) {
+                    check_symbol_part(package, s, style)?;
+                    Self::internal(package, name)
+                } else if let Some((package, name)) = s.split_once(SYMBOL_PACKAGE_MARKER) {
+                    check_symbol_part(package, s, style)?;
+                    Self::external(package, name)
+                } else {
+                    Self::new(s)
+                }
+            }
+            (None, _) => Self::new(s),
+        };
+        check_symbol_part(symbol.as_ref(), s, style)?;
+        Ok(symbol)
+    }
+
+    ///
+    /// Parse a symbol, as for [`Symbol::parse`], after applying Unicode NFC normalization to `s`.
+    ///
+    #[cfg(feature = "normalization")]
+    pub fn parse_normalized<S>(s: S, style: LanguageStyle) -> Result<Self, Error>
+    where
+        S: AsRef<str>,
+    {
+        Self::parse(s.as_ref().nfc().collect::<String>(), style)
+    }
+
+    pub fn qualifier(&self) -> Option<&SymbolQualifier> {
+        self.qualifier.as_ref()
+    }
+
+    pub fn is_qualified(&self) -> bool {
+        self.qualifier.is_some()
+    }
+
+    /// Returns the name of this symbol, without any qualifier.
+    pub fn inner(&self) -> String {
+        self.name.clone()
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations ❱ Keyword
+// ------------------------------------------------------------------------------------------------
+
+/// Keywords are not qualified, so it is an error to convert a qualified symbol.
+impl TryFrom<Symbol> for Keyword {
+    type Error = Error;
+
+    fn try_from(value: Symbol) -> Result<Self, Self::Error> {
+        if value.is_qualified() {
+            Err(invalid_value(format!(
+                "the qualified symbol {value:?} cannot be a keyword"
+            )))
+        } else {
+            Ok(Self(value))
+        }
+    }
+}
+
+impl From<Keyword> for Symbol {
+    fn from(value: Keyword) -> Self {
+        value.0
+    }
+}
+
+impl From<Keyword> for String {
+    fn from(value: Keyword) -> Self {
+        value.0.into()
+    }
+}
+
+impl Keyword {
+    pub fn new<S>(s: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self(Symbol::new(s.into()))
+    }
+
+    ///
+    /// Parse a keyword name, without any prefix, returning an error if `s` is not a valid
+    /// identifier in `style`, or would need escaping to be written.
+    ///
+    pub fn parse<S>(s: S, style: LanguageStyle) -> Result<Self, Error>
+    where
+        S: AsRef<str>,
+    {
+        let s = s.as_ref();
+        check_symbol_part(s, s, style)?;
+        Ok(Self::new(s))
+    }
+
+    ///
+    /// Parse a keyword name, as for [`Keyword::parse`], after applying Unicode NFC normalization
+    /// to `s`.
+    ///
+    #[cfg(feature = "normalization")]
+    pub fn parse_normalized<S>(s: S, style: LanguageStyle) -> Result<Self, Error>
+    where
+        S: AsRef<str>,
+    {
+        Self::parse(s.as_ref().nfc().collect::<String>(), style)
+    }
+
+    pub fn inner(&self) -> Symbol {
+        self.0.clone()
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations ❱ Value::Bool
+// ------------------------------------------------------------------------------------------------
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl From<&bool> for Value {
+    fn from(value: &bool) -> Self {
+        Self::Bool(*value)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations ❱ Value::Number
+// ------------------------------------------------------------------------------------------------
+
+impl<N: Into<Number>> From<N> for Value {
+    fn from(value: N) -> Self {
+        Self::Number(value.into())
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations ❱ Value::Character
+// ------------------------------------------------------------------------------------------------
+
+impl From<char> for Value {
+    fn from(value: char) -> Self {
+        Self::Character(value)
+    }
+}
+
+impl From<&char> for Value {
+    fn from(value: &char) -> Self {
+        Self::Character(*value)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations ❱ Value::String
+// ------------------------------------------------------------------------------------------------
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+impl From<&String> for Value {
+    fn from(value: &String) -> Self {
+        Self::String(value.clone())
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Self::String(value.to_string())
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations ❱ Value::Symbol
+// ------------------------------------------------------------------------------------------------
+
+impl From<Symbol> for Value {
+    fn from(value: Symbol) -> Self {
+        Self::Symbol(value)
+    }
+}
+
+impl From<&Symbol> for Value {
+    fn from(value: &Symbol) -> Self {
+        Self::Symbol(value.clone())
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations ❱ Value::Keyword
+// ------------------------------------------------------------------------------------------------
+
+impl From<Keyword> for Value {
+    fn from(value: Keyword) -> Self {
+        Self::Keyword(value)
+    }
+}
+
+impl From<&Keyword> for Value {
+    fn from(value: &Keyword) -> Self {
+        Self::Keyword(value.clone())
     }
 }
 
@@ -340,11 +1192,2000 @@ impl FromIterator<Value> for Value {
 }
 
 // ------------------------------------------------------------------------------------------------
-// Implementations ❱ Value
+// Implementations ❱ Value::DottedList
 // ------------------------------------------------------------------------------------------------
 
-impl Value {
-    pub fn empty_list() -> Self {
-        Self::List(Vec::default())
+impl From<DottedList> for Value {
+    fn from(value: DottedList) -> Self {
+        Self::DottedList(value)
+    }
+}
+
+impl From<&DottedList> for Value {
+    fn from(value: &DottedList) -> Self {
+        Self::DottedList(value.clone())
+    }
+}
+
+impl DottedList {
+    ///
+    /// Create a dotted list, returning an error if `items` is empty as there must be at least
+    /// one item before the dot; see [`Value::improper_list`] for a constructor that accepts an
+    /// empty list of items.
+    ///
+    pub fn new<V>(items: Vec<Value>, tail: V) -> Result<Self, Error>
+    where
+        V: Into<Value>,
+    {
+        if items.is_empty() {
+            Err(invalid_value(
+                "a dotted list must have at least one item before the dot",
+            ))
+        } else {
+            Ok(Self {
+                items,
+                tail: Box::new(tail.into()),
+            })
+        }
+    }
+
+    pub fn pair<V1, V2>(car: V1, cdr: V2) -> Self
+    where
+        V1: Into<Value>,
+        V2: Into<Value>,
+    {
+        Self {
+            items: vec![car.into()],
+            tail: Box::new(cdr.into()),
+        }
+    }
+
+    pub fn items(&self) -> &Vec<Value> {
+        &self.items
+    }
+
+    pub fn tail(&self) -> &Value {
+        &self.tail
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations ❱ Value::Map
+// ------------------------------------------------------------------------------------------------
+
+impl From<Map> for Value {
+    fn from(value: Map) -> Self {
+        Self::Map(value)
+    }
+}
+
+impl From<&Map> for Value {
+    fn from(value: &Map) -> Self {
+        Self::Map(value.clone())
+    }
+}
+
+impl<K, V> FromIterator<(K, V)> for Map
+where
+    K: Into<Value>,
+    V: Into<Value>,
+{
+    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
+        Self {
+            test: MapTest::default(),
+            entries: iter
+                .into_iter()
+                .map(|(k, v)| (k.into(), v.into()))
+                .collect(),
+        }
+    }
+}
+
+impl Map {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_test(self, test: MapTest) -> Self {
+        let mut self_mut = self;
+        self_mut.set_test(test);
+        self_mut
+    }
+
+    pub fn test(&self) -> &MapTest {
+        &self.test
+    }
+
+    pub fn set_test(&mut self, test: MapTest) {
+        self.test = test;
+    }
+
+    ///
+    /// Inserts `value` for `key`, comparing keys using this map's test. If the key is already
+    /// present its value is replaced, keeping the entry's position, and the previous value is
+    /// returned; otherwise the new entry is appended and `None` is returned.
+    ///
+    pub fn insert<K, V>(&mut self, key: K, value: V) -> Option<Value>
+    where
+        K: Into<Value>,
+        V: Into<Value>,
+    {
+        let key = key.into();
+        let value = value.into();
+        match self.position(&key) {
+            Some(index) => Some(std::mem::replace(&mut self.entries[index].1, value)),
+            None => {
+                self.entries.push((key, value));
+                None
+            }
+        }
+    }
+
+    pub fn entries(&self) -> &Vec<(Value, Value)> {
+        &self.entries
+    }
+
+    /// Returns the value for `key`, comparing keys using this map's test.
+    pub fn get(&self, key: &Value) -> Option<&Value> {
+        self.position(key).map(|index| &self.entries[index].1)
+    }
+
+    /// Returns a mutable reference to the value for `key`, comparing keys using this map's test.
+    pub fn get_mut(&mut self, key: &Value) -> Option<&mut Value> {
+        self.position(key).map(|index| &mut self.entries[index].1)
+    }
+
+    /// Removes the entry for `key`, returning its value, comparing keys using this map's test.
+    pub fn remove(&mut self, key: &Value) -> Option<Value> {
+        self.position(key).map(|index| self.entries.remove(index).1)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn position(&self, key: &Value) -> Option<usize> {
+        self.entries
+            .iter()
+            .position(|(k, _)| k.is_equivalent(key, self.test))
+    }
+
+    /// Returns the entries as an association list, `((k . v) ...)`.
+    pub fn to_association_list(&self) -> Value {
+        self.entries
+            .iter()
+            .map(|(k, v)| Value::pair(k.clone(), v.clone()))
+            .collect()
+    }
+
+    /// Returns the entries as a property list, `(k v ...)`.
+    pub fn to_property_list(&self) -> Value {
+        self.entries
+            .iter()
+            .flat_map(|(k, v)| [k.clone(), v.clone()])
+            .collect()
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations ❱ Value::Quoted
+// ------------------------------------------------------------------------------------------------
+
+impl From<Quoted> for Value {
+    fn from(value: Quoted) -> Self {
+        Self::Quoted(value)
+    }
+}
+
+impl From<&Quoted> for Value {
+    fn from(value: &Quoted) -> Self {
+        Self::Quoted(value.clone())
+    }
+}
+
+impl Quoted {
+    pub fn new<V>(kind: QuoteKind, datum: V) -> Self
+    where
+        V: Into<Value>,
+    {
+        Self {
+            kind,
+            datum: Box::new(datum.into()),
+        }
+    }
+
+    pub fn kind(&self) -> QuoteKind {
+        self.kind
+    }
+
+    pub fn datum(&self) -> &Value {
+        &self.datum
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations ❱ Value::Struct
+// ------------------------------------------------------------------------------------------------
+
+impl From<Struct> for Value {
+    fn from(value: Struct) -> Self {
+        Self::Struct(value)
+    }
+}
+
+impl From<&Struct> for Value {
+    fn from(value: &Struct) -> Self {
+        Self::Struct(value.clone())
+    }
+}
+
+impl Struct {
+    pub fn new<I>(name: Symbol, fields: I) -> Self
+    where
+        I: IntoIterator<Item = Value>,
+    {
+        Self {
+            name,
+            fields: Vec::from_iter(fields),
+        }
+    }
+
+    pub fn name(&self) -> &Symbol {
+        &self.name
+    }
+
+    pub fn fields(&self) -> &Vec<Value> {
+        &self.fields
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations ❱ Value::Array
+// ------------------------------------------------------------------------------------------------
+
+impl From<Array> for Value {
+    fn from(value: Array) -> Self {
+        Self::Array(value)
+    }
+}
+
+impl From<&Array> for Value {
+    fn from(value: &Array) -> Self {
+        Self::Array(value.clone())
+    }
+}
+
+impl Array {
+    ///
+    /// Construct a new array, returning an error if the number of elements is not the product
+    /// of the dimensions.
+    ///
+    pub fn new<I>(dimensions: Vec<usize>, elements: I) -> Result<Self, Error>
+    where
+        I: IntoIterator<Item = Value>,
+    {
+        let elements = Vec::from_iter(elements);
+        let size = dimensions
+            .iter()
+            .try_fold(1_usize, |size, dimension| size.checked_mul(*dimension));
+        if size == Some(elements.len()) {
+            Ok(Self {
+                dimensions,
+                elements,
+            })
+        } else {
+            Err(invalid_value(format!(
+                "an array with dimensions {dimensions:?} cannot hold {} elements",
+                elements.len()
+            )))
+        }
+    }
+
+    pub fn dimensions(&self) -> &Vec<usize> {
+        &self.dimensions
+    }
+
+    pub fn rank(&self) -> usize {
+        self.dimensions.len()
+    }
+
+    pub fn elements(&self) -> &Vec<Value> {
+        &self.elements
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations ❱ Value::ModifiedChar
+// ------------------------------------------------------------------------------------------------
+
+impl From<ModifiedChar> for Value {
+    fn from(value: ModifiedChar) -> Self {
+        Self::ModifiedChar(value)
+    }
+}
+
+impl From<&ModifiedChar> for Value {
+    fn from(value: &ModifiedChar) -> Self {
+        Self::ModifiedChar(value.clone())
+    }
+}
+
+impl From<char> for ModifiedChar {
+    fn from(value: char) -> Self {
+        Self::new(value)
+    }
+}
+
+impl ModifiedChar {
+    pub fn new(character: char) -> Self {
+        Self {
+            modifiers: Vec::default(),
+            character,
+        }
+    }
+
+    pub fn with_modifier(self, modifier: CharModifier) -> Self {
+        let mut self_mut = self;
+        self_mut.add_modifier(modifier);
+        self_mut
+    }
+
+    pub fn modifiers(&self) -> &Vec<CharModifier> {
+        &self.modifiers
+    }
+
+    /// Add a modifier; modifiers are written in the order added and duplicates are ignored.
+    pub fn add_modifier(&mut self, modifier: CharModifier) {
+        if !self.modifiers.contains(&modifier) {
+            self.modifiers.push(modifier);
+        }
+    }
+
+    pub fn character(&self) -> char {
+        self.character
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations ❱ Value::PropertizedString
+// ------------------------------------------------------------------------------------------------
+
+impl From<PropertizedString> for Value {
+    fn from(value: PropertizedString) -> Self {
+        Self::PropertizedString(value)
+    }
+}
+
+impl From<&PropertizedString> for Value {
+    fn from(value: &PropertizedString) -> Self {
+        Self::PropertizedString(value.clone())
+    }
+}
+
+impl PropertizedString {
+    pub fn new<S>(text: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            text: text.into(),
+            properties: Vec::default(),
+        }
+    }
+
+    pub fn with_properties(self, properties: TextProperties) -> Result<Self, Error> {
+        let mut self_mut = self;
+        self_mut.add_properties(properties)?;
+        Ok(self_mut)
+    }
+
+    pub fn text(&self) -> &String {
+        &self.text
+    }
+
+    pub fn properties(&self) -> &Vec<TextProperties> {
+        &self.properties
+    }
+
+    ///
+    /// Adds the properties for a range of characters, returning an error if the range ends
+    /// beyond the number of characters in the text.
+    ///
+    pub fn add_properties(&mut self, properties: TextProperties) -> Result<(), Error> {
+        let length = self.text.chars().count();
+        if properties.end > length {
+            return Err(invalid_value(format!(
+                "the text property range {}..{} ends beyond the {length} characters of the text",
+                properties.start, properties.end
+            )));
+        }
+        self.properties.push(properties);
+        Ok(())
+    }
+}
+
+impl TextProperties {
+    ///
+    /// Create an empty set of properties for the characters from `start` to `end`, returning an
+    /// error if `start` is after `end`.
+    ///
+    pub fn new(start: usize, end: usize) -> Result<Self, Error> {
+        if start > end {
+            return Err(invalid_value(format!(
+                "the text property range {start}..{end} starts after it ends"
+            )));
+        }
+        Ok(Self {
+            start,
+            end,
+            properties: Vec::default(),
+        })
+    }
+
+    pub fn with_property<V>(self, name: Symbol, value: V) -> Self
+    where
+        V: Into<Value>,
+    {
+        let mut self_mut = self;
+        self_mut.insert(name, value);
+        self_mut
+    }
+
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    pub fn end(&self) -> usize {
+        self.end
+    }
+
+    pub fn properties(&self) -> &Vec<(Symbol, Value)> {
+        &self.properties
+    }
+
+    pub fn insert<V>(&mut self, name: Symbol, value: V)
+    where
+        V: Into<Value>,
+    {
+        self.properties.push((name, value.into()));
+    }
+
+    /// Returns the properties as a property list, `(name value ...)`.
+    pub fn to_property_list(&self) -> Value {
+        self.properties
+            .iter()
+            .flat_map(|(k, v)| [Value::from(k), v.clone()])
+            .collect()
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations ❱ Value::Node
+// ------------------------------------------------------------------------------------------------
+
+impl From<Node> for Value {
+    fn from(value: Node) -> Self {
+        Self::Node(value)
+    }
+}
+
+impl From<&Node> for Value {
+    fn from(value: &Node) -> Self {
+        Self::Node(value.clone())
+    }
+}
+
+impl From<NodeKind> for Node {
+    fn from(value: NodeKind) -> Self {
+        Self::new(value)
+    }
+}
+
+impl Node {
+    pub fn new(kind: NodeKind) -> Self {
+        Self {
+            kind,
+            field: None,
+            range: None,
+            children: Vec::default(),
+        }
+    }
+
+    pub fn named<S>(kind: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self::new(NodeKind::Named(kind.into()))
+    }
+
+    pub fn anonymous<S>(text: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self::new(NodeKind::Anonymous(text.into()))
+    }
+
+    pub fn missing_named<S>(kind: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self::new(NodeKind::Missing {
+            kind: kind.into(),
+            named: true,
+        })
+    }
+
+    pub fn missing_anonymous<S>(text: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self::new(NodeKind::Missing {
+            kind: text.into(),
+            named: false,
+        })
+    }
+
+    pub fn error() -> Self {
+        Self::new(NodeKind::Error)
+    }
+
+    pub fn kind(&self) -> &NodeKind {
+        &self.kind
+    }
+
+    pub fn with_field<S>(self, field: S) -> Self
+    where
+        S: Into<String>,
+    {
+        let mut self_mut = self;
+        self_mut.set_field(field);
+        self_mut
+    }
+
+    pub fn field(&self) -> Option<&String> {
+        self.field.as_ref()
+    }
+
+    pub fn set_field<S>(&mut self, field: S)
+    where
+        S: Into<String>,
+    {
+        self.field = Some(field.into());
+    }
+
+    pub fn unset_field(&mut self) {
+        self.field = None;
+    }
+
+    pub fn with_range<P1, P2>(self, start: P1, end: P2) -> Self
+    where
+        P1: Into<Point>,
+        P2: Into<Point>,
+    {
+        let mut self_mut = self;
+        self_mut.set_range(start, end);
+        self_mut
+    }
+
+    pub fn range(&self) -> Option<&(Point, Point)> {
+        self.range.as_ref()
+    }
+
+    pub fn set_range<P1, P2>(&mut self, start: P1, end: P2)
+    where
+        P1: Into<Point>,
+        P2: Into<Point>,
+    {
+        self.range = Some((start.into(), end.into()));
+    }
+
+    pub fn unset_range(&mut self) {
+        self.range = None;
+    }
+
+    pub fn with_child(self, child: Node) -> Self {
+        let mut self_mut = self;
+        self_mut.add_child(child);
+        self_mut
+    }
+
+    pub fn children(&self) -> &Vec<Node> {
+        &self.children
+    }
+
+    pub fn add_child(&mut self, child: Node) {
+        self.children.push(child);
+    }
+}
+
+impl From<(usize, usize)> for Point {
+    fn from(value: (usize, usize)) -> Self {
+        Self::new(value.0, value.1)
+    }
+}
+
+impl Point {
+    pub fn new(row: usize, column: usize) -> Self {
+        Self { row, column }
+    }
+
+    pub fn row(&self) -> usize {
+        self.row
+    }
+
+    pub fn column(&self) -> usize {
+        self.column
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations ❱ Value::Regexp
+// ------------------------------------------------------------------------------------------------
+
+impl From<Regexp> for Value {
+    fn from(value: Regexp) -> Self {
+        Self::Regexp(value)
+    }
+}
+
+impl From<&Regexp> for Value {
+    fn from(value: &Regexp) -> Self {
+        Self::Regexp(value.clone())
+    }
+}
+
+impl Regexp {
+    pub fn new<S>(pattern: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            syntax: RegexpSyntax::default(),
+            byte_regexp: false,
+            pattern: pattern.into(),
+        }
+    }
+
+    pub fn with_syntax(self, syntax: RegexpSyntax) -> Self {
+        let mut self_mut = self;
+        self_mut.set_syntax(syntax);
+        self_mut
+    }
+
+    pub fn syntax(&self) -> &RegexpSyntax {
+        &self.syntax
+    }
+
+    pub fn set_syntax(&mut self, syntax: RegexpSyntax) {
+        self.syntax = syntax;
+    }
+
+    pub fn with_byte_regexp(self, byte_regexp: bool) -> Self {
+        let mut self_mut = self;
+        self_mut.set_byte_regexp(byte_regexp);
+        self_mut
+    }
+
+    pub fn is_byte_regexp(&self) -> bool {
+        self.byte_regexp
+    }
+
+    pub fn set_byte_regexp(&mut self, byte_regexp: bool) {
+        self.byte_regexp = byte_regexp;
+    }
+
+    pub fn pattern(&self) -> &String {
+        &self.pattern
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations ❱ Value::Path
+// ------------------------------------------------------------------------------------------------
+
+impl From<PathBuf> for Value {
+    fn from(value: PathBuf) -> Self {
+        Self::Path(value)
+    }
+}
+
+impl From<&PathBuf> for Value {
+    fn from(value: &PathBuf) -> Self {
+        Self::Path(value.clone())
+    }
+}
+
+impl From<&Path> for Value {
+    fn from(value: &Path) -> Self {
+        Self::Path(value.to_path_buf())
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations ❱ Value::Shared
+// ------------------------------------------------------------------------------------------------
+
+impl From<Shared> for Value {
+    fn from(value: Shared) -> Self {
+        Self::Shared(value)
+    }
+}
+
+impl From<&Shared> for Value {
+    fn from(value: &Shared) -> Self {
+        Self::Shared(value.clone())
+    }
+}
+
+impl Debug for Shared {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // the value is not included as it may contain this shared value.
+        f.debug_tuple("Shared")
+            .field(&Arc::as_ptr(&self.0))
+            .finish()
+    }
+}
+
+impl PartialEq for Shared {
+    fn eq(&self, other: &Self) -> bool {
+        self.ptr_eq(other)
+    }
+}
+
+impl Eq for Shared {}
+
+impl PartialOrd for Shared {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Shared values are ordered by identity, which is stable but otherwise arbitrary.
+impl Ord for Shared {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_ptr().cmp(&other.as_ptr())
+    }
+}
+
+impl Hash for Shared {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_ptr().hash(state);
+    }
+}
+
+impl Shared {
+    pub fn new<V>(value: V) -> Self
+    where
+        V: Into<Value>,
+    {
+        Self(Arc::new(OnceLock::from(value.into())))
+    }
+
+    pub fn empty() -> Self {
+        Self(Arc::default())
+    }
+
+    ///
+    /// Set the value of an empty shared value, returning the value as an error if it has
+    /// already been set.
+    ///
+    pub fn set<V>(&self, value: V) -> Result<(), Value>
+    where
+        V: Into<Value>,
+    {
+        self.0.set(value.into())
+    }
+
+    pub fn get(&self) -> Option<&Value> {
+        self.0.get()
+    }
+
+    /// Returns `true` if `self` and `other` are the same shared value.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+
+    pub(crate) fn as_ptr(&self) -> *const OnceLock<Value> {
+        Arc::as_ptr(&self.0)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations ❱ Value::DatumLabel
+// ------------------------------------------------------------------------------------------------
+
+impl From<DatumLabel> for Value {
+    fn from(value: DatumLabel) -> Self {
+        Self::DatumLabel(value)
+    }
+}
+
+impl From<&DatumLabel> for Value {
+    fn from(value: &DatumLabel) -> Self {
+        Self::DatumLabel(value.clone())
+    }
+}
+
+impl DatumLabel {
+    pub fn define<V>(label: usize, value: V) -> Self
+    where
+        V: Into<Value>,
+    {
+        Self::Define(label, Box::new(value.into()))
+    }
+
+    pub fn reference(label: usize) -> Self {
+        Self::Reference(label)
+    }
+
+    pub fn label(&self) -> usize {
+        match self {
+            Self::Define(label, _) | Self::Reference(label) => *label,
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations ❱ Value::Commented
+// ------------------------------------------------------------------------------------------------
+
+impl Comment {
+    pub fn line<S>(level: LineCommentLevel, text: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self::Line(level, text.into())
+    }
+
+    pub fn block<S>(text: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self::Block(text.into())
+    }
+
+    pub fn datum<V>(datum: V) -> Self
+    where
+        V: Into<Value>,
+    {
+        Self::Datum(Box::new(datum.into()))
+    }
+
+    pub fn is_line(&self) -> bool {
+        matches!(self, Self::Line(_, _))
+    }
+}
+
+impl From<Commented> for Value {
+    fn from(value: Commented) -> Self {
+        Self::Commented(value)
+    }
+}
+
+impl From<&Commented> for Value {
+    fn from(value: &Commented) -> Self {
+        Self::Commented(value.clone())
+    }
+}
+
+impl Commented {
+    pub fn new<V>(value: V) -> Self
+    where
+        V: Into<Value>,
+    {
+        Self {
+            leading: Vec::default(),
+            value: Box::new(value.into()),
+            trailing: None,
+        }
+    }
+
+    pub fn with_leading(self, comment: Comment) -> Self {
+        let mut self_mut = self;
+        self_mut.add_leading(comment);
+        self_mut
+    }
+
+    pub fn with_trailing(self, comment: Comment) -> Self {
+        let mut self_mut = self;
+        self_mut.set_trailing(comment);
+        self_mut
+    }
+
+    pub fn leading(&self) -> &Vec<Comment> {
+        &self.leading
+    }
+
+    pub fn add_leading(&mut self, comment: Comment) {
+        self.leading.push(comment);
+    }
+
+    pub fn value(&self) -> &Value {
+        &self.value
+    }
+
+    pub fn set_value<V>(&mut self, value: V)
+    where
+        V: Into<Value>,
+    {
+        *self.value = value.into();
+    }
+
+    pub fn trailing(&self) -> Option<&Comment> {
+        self.trailing.as_ref()
+    }
+
+    pub fn set_trailing(&mut self, comment: Comment) {
+        self.trailing = Some(comment);
+    }
+
+    pub fn unset_trailing(&mut self) {
+        self.trailing = None;
+    }
+
+    /// Returns `true` if the trailing comment is a line comment, in which case nothing else may
+    /// be written on the same line after this value.
+    pub fn ends_with_line_comment(&self) -> bool {
+        self.trailing
+            .as_ref()
+            .map(Comment::is_line)
+            .unwrap_or_default()
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations ❱ Value
+// ------------------------------------------------------------------------------------------------
+
+impl Value {
+    pub fn empty_list() -> Self {
+        Self::List(Vec::default())
+    }
+
+    pub fn bytes<B>(bytes: B) -> Self
+    where
+        B: Into<Vec<u8>>,
+    {
+        Self::Bytes(bytes.into())
+    }
+
+    pub fn empty_vector() -> Self {
+        Self::Vector(Vec::default())
+    }
+
+    pub fn vector<I>(values: I) -> Self
+    where
+        I: IntoIterator<Item = Value>,
+    {
+        Self::Vector(Vec::from_iter(values))
+    }
+
+    pub fn pair<V1, V2>(car: V1, cdr: V2) -> Self
+    where
+        V1: Into<Value>,
+        V2: Into<Value>,
+    {
+        Self::DottedList(DottedList::pair(car, cdr))
+    }
+
+    pub fn quote<V>(datum: V) -> Self
+    where
+        V: Into<Value>,
+    {
+        Self::Quoted(Quoted::new(QuoteKind::Quote, datum))
+    }
+
+    pub fn quasi_quote<V>(datum: V) -> Self
+    where
+        V: Into<Value>,
+    {
+        Self::Quoted(Quoted::new(QuoteKind::QuasiQuote, datum))
+    }
+
+    pub fn unquote<V>(datum: V) -> Self
+    where
+        V: Into<Value>,
+    {
+        Self::Quoted(Quoted::new(QuoteKind::Unquote, datum))
+    }
+
+    pub fn unquote_splicing<V>(datum: V) -> Self
+    where
+        V: Into<Value>,
+    {
+        Self::Quoted(Quoted::new(QuoteKind::UnquoteSplicing, datum))
+    }
+
+    pub fn function(name: Symbol) -> Self {
+        Self::Quoted(Quoted::new(QuoteKind::Function, name))
+    }
+
+    pub fn bit_vector<I>(bits: I) -> Self
+    where
+        I: IntoIterator<Item = bool>,
+    {
+        Self::BitVector(Vec::from_iter(bits))
+    }
+
+    ///
+    /// Create an improper list `(items ... . tail)`. As `( . tail)` is not a list, if `items`
+    /// is empty the result is the `tail` value itself, as a Lisp reader would construct it.
+    ///
+    pub fn improper_list<V>(items: Vec<Value>, tail: V) -> Self
+    where
+        V: Into<Value>,
+    {
+        if items.is_empty() {
+            tail.into()
+        } else {
+            Self::DottedList(DottedList {
+                items,
+                tail: Box::new(tail.into()),
+            })
+        }
+    }
+
+    pub fn boxed<V>(value: V) -> Self
+    where
+        V: Into<Value>,
+    {
+        Self::Boxed(Box::new(value.into()))
+    }
+
+    pub fn with_leading_comment(self, comment: Comment) -> Self {
+        match self {
+            Self::Commented(commented) => Self::Commented(commented.with_leading(comment)),
+            _ => Self::Commented(Commented::new(self).with_leading(comment)),
+        }
+    }
+
+    pub fn with_trailing_comment(self, comment: Comment) -> Self {
+        match self {
+            Self::Commented(commented) => Self::Commented(commented.with_trailing(comment)),
+            _ => Self::Commented(Commented::new(self).with_trailing(comment)),
+        }
+    }
+
+    ///
+    /// Returns `true` if `self` and `other` are equivalent under `test`, one of Lisp's `eq?`,
+    /// `eqv?`, or `equal?` predicates.
+    ///
+    pub fn is_equivalent(&self, other: &Value, test: MapTest) -> bool {
+        match test {
+            MapTest::Equal => self.is_equal(other),
+            MapTest::Eqv => self.is_eqv(other),
+            MapTest::Eq => self.is_eq(other),
+        }
+    }
+
+    ///
+    /// Returns `true` if `self` and `other` are the same object, as for Lisp's `eq?`. Values
+    /// have no identity beyond their location in memory, so this is `true` for the same value, for
+    /// the same shared value, and for booleans, characters, symbols, keywords, empty lists, and
+    /// `i64` integers that are equal, as these are immediate values in most implementations.
+    /// Uninterned symbols are the exception, each `#:name` is a distinct object and so is only
+    /// `eq?` to itself, or to the same shared value.
+    ///
+    pub fn is_eq(&self, other: &Value) -> bool {
+        std::ptr::eq(self, other)
+            || match (self, other) {
+                (Self::Bool(lhs), Self::Bool(rhs)) => lhs == rhs,
+                (Self::Character(lhs), Self::Character(rhs)) => lhs == rhs,
+                (Self::Symbol(lhs), Self::Symbol(rhs)) => {
+                    lhs == rhs && lhs.qualifier() != Some(&SymbolQualifier::Uninterned)
+                }
+                (Self::Keyword(lhs), Self::Keyword(rhs)) => lhs == rhs,
+                (Self::List(lhs), Self::List(rhs)) => lhs.is_empty() && rhs.is_empty(),
+                (Self::Number(Number::Integer(lhs)), Self::Number(Number::Integer(rhs))) => {
+                    lhs == rhs
+                }
+                (Self::Shared(lhs), Self::Shared(rhs)) => lhs.ptr_eq(rhs),
+                _ => false,
+            }
+    }
+
+    ///
+    /// Returns `true` if `self` and `other` are equivalent, as for Lisp's `eqv?`; this extends
+    /// [`Value::is_eq`] to compare all numbers by kind and value, so that `1` and `1.0`, or
+    /// `0.0` and `-0.0`, are not equivalent but two NaNs with the same bits are.
+    ///
+    pub fn is_eqv(&self, other: &Value) -> bool {
+        match (self, other) {
+            (Self::Number(lhs), Self::Number(rhs)) => lhs == rhs,
+            _ => self.is_eq(other),
+        }
+    }
+
+    ///
+    /// Returns `true` if `self` and `other` are structurally equal, as for Lisp's `equal?`.
+    /// Unlike `==`, comments are ignored, shared values are compared by content, and maps are
+    /// compared by key regardless of the order of their entries; cyclic structures are equal if
+    /// they cannot be distinguished by following them.
+    ///
+    pub fn is_equal(&self, other: &Value) -> bool {
+        equal_values(self, other, &mut HashSet::default())
+    }
+
+    ///
+    /// Returns `true` if this value, or any value nested within it, has comments attached. Shared
+    /// values are not searched as they may be cyclic.
+    ///
+    pub fn has_comments(&self) -> bool {
+        match self {
+            Self::Commented(_) => true,
+            Self::List(values) | Self::Vector(values) => values.iter().any(Value::has_comments),
+            Self::DottedList(list) => {
+                list.items().iter().any(Value::has_comments) || list.tail().has_comments()
+            }
+            Self::Map(map) => map
+                .entries()
+                .iter()
+                .any(|(k, v)| k.has_comments() || v.has_comments()),
+            Self::Quoted(quoted) => quoted.datum().has_comments(),
+            Self::Boxed(value) => value.has_comments(),
+            Self::Struct(value) => value.fields().iter().any(Value::has_comments),
+            Self::Array(value) => value.elements().iter().any(Value::has_comments),
+            Self::DatumLabel(DatumLabel::Define(_, value)) => value.has_comments(),
+            _ => false,
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations ❱ Value ❱ Accessors
+// ------------------------------------------------------------------------------------------------
+
+impl Value {
+    pub fn is_bool(&self) -> bool {
+        matches!(self, Self::Bool(_))
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Bool(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    pub fn is_number(&self) -> bool {
+        matches!(self, Self::Number(_))
+    }
+
+    pub fn as_number(&self) -> Option<&Number> {
+        match self {
+            Self::Number(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_integer(&self) -> Option<i64> {
+        match self {
+            Self::Number(v) => v.as_integer(),
+            _ => None,
+        }
+    }
+
+    pub fn as_float(&self) -> Option<f64> {
+        match self {
+            Self::Number(Number::Flonum(v)) => Some(*v),
+            _ => None,
+        }
+    }
+
+    pub fn is_character(&self) -> bool {
+        matches!(self, Self::Character(_))
+    }
+
+    pub fn as_character(&self) -> Option<char> {
+        match self {
+            Self::Character(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    pub fn is_string(&self) -> bool {
+        matches!(self, Self::String(_))
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn is_symbol(&self) -> bool {
+        matches!(self, Self::Symbol(_))
+    }
+
+    pub fn as_symbol(&self) -> Option<&Symbol> {
+        match self {
+            Self::Symbol(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn is_keyword(&self) -> bool {
+        matches!(self, Self::Keyword(_))
+    }
+
+    pub fn as_keyword(&self) -> Option<&Keyword> {
+        match self {
+            Self::Keyword(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn is_list(&self) -> bool {
+        matches!(self, Self::List(_))
+    }
+
+    pub fn is_empty_list(&self) -> bool {
+        matches!(self, Self::List(items) if items.is_empty())
+    }
+
+    pub fn as_list(&self) -> Option<&Vec<Value>> {
+        match self {
+            Self::List(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_list_mut(&mut self) -> Option<&mut Vec<Value>> {
+        match self {
+            Self::List(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn is_dotted_list(&self) -> bool {
+        matches!(self, Self::DottedList(_))
+    }
+
+    pub fn as_dotted_list(&self) -> Option<&DottedList> {
+        match self {
+            Self::DottedList(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn is_vector(&self) -> bool {
+        matches!(self, Self::Vector(_))
+    }
+
+    pub fn as_vector(&self) -> Option<&Vec<Value>> {
+        match self {
+            Self::Vector(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_vector_mut(&mut self) -> Option<&mut Vec<Value>> {
+        match self {
+            Self::Vector(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn is_map(&self) -> bool {
+        matches!(self, Self::Map(_))
+    }
+
+    pub fn as_map(&self) -> Option<&Map> {
+        match self {
+            Self::Map(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_map_mut(&mut self) -> Option<&mut Map> {
+        match self {
+            Self::Map(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn is_quoted(&self) -> bool {
+        matches!(self, Self::Quoted(_))
+    }
+
+    pub fn as_quoted(&self) -> Option<&Quoted> {
+        match self {
+            Self::Quoted(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn is_commented(&self) -> bool {
+        matches!(self, Self::Commented(_))
+    }
+
+    pub fn as_commented(&self) -> Option<&Commented> {
+        match self {
+            Self::Commented(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn is_shared(&self) -> bool {
+        matches!(self, Self::Shared(_))
+    }
+
+    pub fn as_shared(&self) -> Option<&Shared> {
+        match self {
+            Self::Shared(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    ///
+    /// Returns the value itself, looking through any comments attached to it and through set
+    /// shared values. This is the value that [`Value::get`] and [`Value::get_in`] return.
+    ///
+    pub fn resolved(&self) -> &Value {
+        match self {
+            Self::Commented(commented) => commented.value().resolved(),
+            Self::Shared(shared) => shared.get().map(Value::resolved).unwrap_or(self),
+            _ => self,
+        }
+    }
+
+    ///
+    /// Returns the value itself, looking through any comments attached to it. Shared values
+    /// cannot be changed once set, and so are not looked through.
+    ///
+    pub fn resolved_mut(&mut self) -> &mut Value {
+        match self {
+            Self::Commented(commented) => commented.value.resolved_mut(),
+            value => value,
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations ❱ Value ❱ Navigation
+// ------------------------------------------------------------------------------------------------
+
+impl Index<usize> for Value {
+    type Output = Value;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        self.get(index)
+            .unwrap_or_else(|| panic!("no item at index {index} in value"))
+    }
+}
+
+impl IndexMut<usize> for Value {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        self.get_mut(index)
+            .unwrap_or_else(|| panic!("no item at index {index} in value"))
+    }
+}
+
+impl From<usize> for Selector {
+    fn from(value: usize) -> Self {
+        Self::Index(value)
+    }
+}
+
+impl From<Keyword> for Selector {
+    fn from(value: Keyword) -> Self {
+        Self::Property(value)
+    }
+}
+
+impl From<&Keyword> for Selector {
+    fn from(value: &Keyword) -> Self {
+        Self::Property(value.clone())
+    }
+}
+
+impl Selector {
+    pub fn index(index: usize) -> Self {
+        Self::Index(index)
+    }
+
+    pub fn key<V>(key: V) -> Self
+    where
+        V: Into<Value>,
+    {
+        Self::Key(key.into())
+    }
+
+    pub fn property<K>(keyword: K) -> Self
+    where
+        K: Into<Keyword>,
+    {
+        Self::Property(keyword.into())
+    }
+}
+
+impl Value {
+    ///
+    /// Returns the item at `index` in a list, vector, or the items of a dotted list.
+    ///
+    pub fn get(&self, index: usize) -> Option<&Value> {
+        match self.resolved() {
+            Self::List(items) | Self::Vector(items) => items.get(index),
+            Self::DottedList(list) => list.items.get(index),
+            _ => None,
+        }
+        .map(Value::resolved)
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut Value> {
+        match self.resolved_mut() {
+            Self::List(items) | Self::Vector(items) => items.get_mut(index),
+            Self::DottedList(list) => list.items.get_mut(index),
+            _ => None,
+        }
+        .map(Value::resolved_mut)
+    }
+
+    ///
+    /// Returns the value for `key` in a map, or in an association list of dotted pairs
+    /// `((k . v) ...)` where keys are compared as for [`Value::is_equal`].
+    ///
+    pub fn assoc(&self, key: &Value) -> Option<&Value> {
+        match self.resolved() {
+            Self::Map(map) => map.get(key),
+            Self::List(items) => association_index(items, key)
+                .and_then(|index| items[index].resolved().as_dotted_list())
+                .map(DottedList::tail),
+            _ => None,
+        }
+        .map(Value::resolved)
+    }
+
+    pub fn assoc_mut(&mut self, key: &Value) -> Option<&mut Value> {
+        match self.resolved_mut() {
+            Self::Map(map) => map.get_mut(key),
+            Self::List(items) => match association_index(items, key) {
+                Some(index) => match items[index].resolved_mut() {
+                    Self::DottedList(pair) => Some(pair.tail.as_mut()),
+                    _ => None,
+                },
+                None => None,
+            },
+            _ => None,
+        }
+        .map(Value::resolved_mut)
+    }
+
+    ///
+    /// Returns the value following the keyword `key` in a property list `(:k v ...)`.
+    ///
+    pub fn plist_get(&self, key: &Keyword) -> Option<&Value> {
+        match self.resolved() {
+            Self::List(items) => property_index(items, key).map(|index| items[index].resolved()),
+            _ => None,
+        }
+    }
+
+    pub fn plist_get_mut(&mut self, key: &Keyword) -> Option<&mut Value> {
+        match self.resolved_mut() {
+            Self::List(items) => match property_index(items, key) {
+                Some(index) => Some(items[index].resolved_mut()),
+                None => None,
+            },
+            _ => None,
+        }
+    }
+
+    ///
+    /// Returns the value selected by `selector`, using [`Value::get`], [`Value::assoc`], or
+    /// [`Value::plist_get`].
+    ///
+    pub fn select(&self, selector: &Selector) -> Option<&Value> {
+        match selector {
+            Selector::Index(index) => self.get(*index),
+            Selector::Key(key) => self.assoc(key),
+            Selector::Property(key) => self.plist_get(key),
+        }
+    }
+
+    pub fn select_mut(&mut self, selector: &Selector) -> Option<&mut Value> {
+        match selector {
+            Selector::Index(index) => self.get_mut(*index),
+            Selector::Key(key) => self.assoc_mut(key),
+            Selector::Property(key) => self.plist_get_mut(key),
+        }
+    }
+
+    ///
+    /// Returns the value at the end of `path`, following each selector in turn; an empty path
+    /// returns this value.
+    ///
+    pub fn get_in(&self, path: &[Selector]) -> Option<&Value> {
+        path.iter()
+            .try_fold(self.resolved(), |value, selector| value.select(selector))
+    }
+
+    pub fn get_in_mut(&mut self, path: &[Selector]) -> Option<&mut Value> {
+        path.iter()
+            .try_fold(self.resolved_mut(), |value, selector| {
+                value.select_mut(selector)
+            })
+    }
+
+    ///
+    /// Inserts `value` at the end of `path`. The last selector names the new value's position:
+    /// an index in a list or vector, at most its length, or a key or keyword that is not already
+    /// present in a map, association list, or property list. New keys are appended. If the path
+    /// does not lead to a suitable position the value is returned as an error.
+    ///
+    pub fn insert_at<V>(&mut self, path: &[Selector], value: V) -> Result<(), Value>
+    where
+        V: Into<Value>,
+    {
+        let value = value.into();
+        let Some((last, path)) = path.split_last() else {
+            return Err(value);
+        };
+        let Some(parent) = self.get_in_mut(path) else {
+            return Err(value);
+        };
+        match (parent, last) {
+            (Self::List(items) | Self::Vector(items), Selector::Index(index))
+                if *index <= items.len() =>
+            {
+                items.insert(*index, value);
+            }
+            (Self::DottedList(list), Selector::Index(index)) if *index <= list.items.len() => {
+                list.items.insert(*index, value);
+            }
+            (Self::Map(map), Selector::Key(key)) if map.get(key).is_none() => {
+                map.insert(key.clone(), value);
+            }
+            (Self::List(items), Selector::Key(key)) if association_index(items, key).is_none() => {
+                items.push(Value::pair(key.clone(), value));
+            }
+            (Self::List(items), Selector::Property(key))
+                if property_index(items, key).is_none() =>
+            {
+                items.push(key.clone().into());
+                items.push(value);
+            }
+            _ => return Err(value),
+        }
+        Ok(())
+    }
+
+    ///
+    /// Removes, and returns, the value at the end of `path`. Removing a key or keyword removes
+    /// the whole entry from a map, association list, or property list. The last item of a dotted
+    /// list cannot be removed.
+    ///
+    pub fn remove_at(&mut self, path: &[Selector]) -> Option<Value> {
+        let (last, path) = path.split_last()?;
+        match (self.get_in_mut(path)?, last) {
+            (Self::List(items) | Self::Vector(items), Selector::Index(index))
+                if *index < items.len() =>
+            {
+                Some(items.remove(*index))
+            }
+            (Self::DottedList(list), Selector::Index(index))
+                if *index < list.items.len() && list.items.len() > 1 =>
+            {
+                Some(list.items.remove(*index))
+            }
+            (Self::Map(map), Selector::Key(key)) => map.remove(key),
+            (Self::List(items), Selector::Key(key)) => {
+                let index = association_index(items, key)?;
+                match items.remove(index).into_resolved() {
+                    Self::DottedList(pair) => Some(*pair.tail),
+                    entry => entry
+                        .resolved()
+                        .as_dotted_list()
+                        .map(|pair| pair.tail().clone()),
+                }
+            }
+            (Self::List(items), Selector::Property(key)) => {
+                let index = property_index(items, key)?;
+                let value = items.remove(index);
+                let _ = items.remove(index - 1);
+                Some(value)
+            }
+            _ => None,
+        }
+    }
+
+    ///
+    /// Replaces the value at the end of `path`, returning the previous value. Any comments
+    /// attached to the previous value are kept. If the path does not lead to a value the new
+    /// value is returned as an error.
+    ///
+    pub fn replace_at<V>(&mut self, path: &[Selector], value: V) -> Result<Value, Value>
+    where
+        V: Into<Value>,
+    {
+        let value = value.into();
+        match self.get_in_mut(path) {
+            Some(target) => Ok(std::mem::replace(target, value)),
+            None => Err(value),
+        }
+    }
+
+    fn into_resolved(self) -> Value {
+        match self {
+            Self::Commented(commented) => commented.value.into_resolved(),
+            value => value,
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations ❱ Value ❱ Children
+// ------------------------------------------------------------------------------------------------
+
+impl Value {
+    ///
+    /// Returns the values directly contained in this value, in the order they are written. Map
+    /// entries are returned as a key followed by its value, and the tail of a dotted list follows
+    /// its items, and the values of a propertized string's text properties are returned in
+    /// order. Shared values are not followed, as they may be cyclic, and comments are not
+    /// included; tree-sitter nodes hold only other nodes, never values, and so have no children.
+    ///
+    pub fn children(&self) -> Vec<&Value> {
+        match self {
+            Self::List(items) | Self::Vector(items) => items.iter().collect(),
+            Self::DottedList(list) => list
+                .items
+                .iter()
+                .chain(std::iter::once(list.tail.as_ref()))
+                .collect(),
+            Self::Map(map) => map.entries.iter().flat_map(|(k, v)| [k, v]).collect(),
+            Self::Quoted(quoted) => vec![quoted.datum.as_ref()],
+            Self::Commented(commented) => vec![commented.value.as_ref()],
+            Self::Boxed(value) | Self::DatumLabel(DatumLabel::Define(_, value)) => {
+                vec![value.as_ref()]
+            }
+            Self::Struct(value) => value.fields.iter().collect(),
+            Self::Array(array) => array.elements.iter().collect(),
+            Self::PropertizedString(string) => string
+                .properties
+                .iter()
+                .flat_map(|properties| properties.properties.iter().map(|(_, v)| v))
+                .collect(),
+            _ => Vec::default(),
+        }
+    }
+
+    ///
+    /// Moves the values returned by [`Value::children`] out of this value, leaving it as an empty
+    /// shell to be filled again by [`Value::restore_children`].
+    ///
+    pub(crate) fn take_children(&mut self) -> Vec<Value> {
+        let take = |value: &mut Value| std::mem::replace(value, Value::empty_list());
+        match self {
+            Self::List(items) | Self::Vector(items) => std::mem::take(items),
+            Self::DottedList(list) => {
+                let mut children = std::mem::take(&mut list.items);
+                children.push(take(&mut list.tail));
+                children
+            }
+            Self::Map(map) => std::mem::take(&mut map.entries)
+                .into_iter()
+                .flat_map(|(k, v)| [k, v])
+                .collect(),
+            Self::Quoted(quoted) => vec![take(&mut quoted.datum)],
+            Self::Commented(commented) => vec![take(&mut commented.value)],
+            Self::Boxed(value) | Self::DatumLabel(DatumLabel::Define(_, value)) => {
+                vec![take(value)]
+            }
+            Self::Struct(value) => std::mem::take(&mut value.fields),
+            Self::Array(array) => std::mem::take(&mut array.elements),
+            Self::PropertizedString(string) => string
+                .properties
+                .iter_mut()
+                .flat_map(|properties| properties.properties.iter_mut().map(|(_, v)| take(v)))
+                .collect(),
+            _ => Vec::default(),
+        }
+    }
+
+    ///
+    /// Moves `children`, as returned by [`Value::take_children`], back into this value.
+    ///
+    pub(crate) fn restore_children(&mut self, children: Vec<Value>) {
+        let mut children = children.into_iter();
+        let mut next = || children.next().expect("missing child value");
+        match self {
+            Self::List(items) | Self::Vector(items) => items.extend(children),
+            Self::DottedList(list) => {
+                list.items.extend(children);
+                *list.tail = list.items.pop().expect("missing dotted list tail");
+            }
+            Self::Map(map) => {
+                while let Some(key) = children.next() {
+                    map.entries
+                        .push((key, children.next().expect("missing map value")));
+                }
+            }
+            Self::Quoted(quoted) => *quoted.datum = next(),
+            Self::Commented(commented) => *commented.value = next(),
+            Self::Boxed(value) | Self::DatumLabel(DatumLabel::Define(_, value)) => **value = next(),
+            Self::Struct(value) => value.fields.extend(children),
+            Self::Array(array) => array.elements.extend(children),
+            Self::PropertizedString(string) => string
+                .properties
+                .iter_mut()
+                .flat_map(|properties| properties.properties.iter_mut())
+                .for_each(|(_, v)| *v = next()),
+            _ => {}
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Values
+// ------------------------------------------------------------------------------------------------
+
+const SYMBOL_UNINTERNED_PREFIX: &str = "#:";
+const SYMBOL_PACKAGE_MARKER: char = ':';
+const SYMBOL_INTERNAL_MARKER: &str = "::";
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Check one part, the name or qualifier, of the symbol `symbol` against the identifier grammar
+/// of `style`. Tree-sitter node and field names are restricted to ASCII letters, digits, and
+/// underscores; the Lisp styles reject anything that would need escaping, or would be read as
+/// something other than a symbol. Racket names may start with `#%`. The Common Lisp reader
+/// upcases unescaped names, so `foo` is read back as `FOO`; names are written in lower case to
+/// stand for their upper case symbol, and an upper case letter, which could only be kept as
+/// written within `|...|`, is rejected.
+///
+fn check_symbol_part(part: &str, symbol: &str, style: LanguageStyle) -> Result<(), Error> {
+    let invalid = |reason: String| {
+        Err(symbol_parser_error(format!(
+            "{symbol:?} is not a valid symbol in {style:?}; {reason}"
+        )))
+    };
+    if part.is_empty() {
+        return invalid("it has an empty name".to_string());
+    }
+    if style == LanguageStyle::TreeSitter {
+        return match part.char_indices().find(|(i, c)| {
+            !(c.is_ascii_alphabetic() || *c == '_' || (*i > 0 && c.is_ascii_digit()))
+        }) {
+            Some((i, c)) => invalid(format!("{c:?} at index {i} is not allowed")),
+            None => Ok(()),
+        };
+    }
+    match symbol_part_escape_reason(part, style) {
+        Some(reason) => invalid(reason),
+        None => Ok(()),
+    }
+}
+
+///
+/// Returns the reason that `part`, the name or qualifier of a symbol, must be wrapped in `|...|`
+/// to be read back as the same symbol in one of the Lisp styles, or `None` if it may be written
+/// as is. This is shared by [`Symbol::parse`] and the writer so that any name accepted by the
+/// former is written unescaped, and any other name is escaped.
+///
+pub(crate) fn symbol_part_escape_reason(part: &str, style: LanguageStyle) -> Option<String> {
+    if part.is_empty() {
+        return Some("it has an empty name".to_string());
+    }
+    let racket_prefixed = style == LanguageStyle::Racket && part.starts_with("#%");
+    if !racket_prefixed {
+        if part == "." {
+            return Some("it would be read as a dot".to_string());
+        }
+        if is_number_like(part, style) {
+            return Some("it would be read as a number".to_string());
+        }
+        let first = part.chars().next().unwrap();
+        if first == '#' || (style == LanguageStyle::EmacsLisp && first == '?') {
+            return Some(format!(
+                "it starts with {first:?} which begins another syntax"
+            ));
+        }
+    }
+    for (i, c) in part.char_indices() {
+        let needs_escape = c.is_whitespace()
+            || c.is_control()
+            || matches!(
+                c,
+                '(' | ')' | '[' | ']' | '"' | ';' | '\'' | '`' | ',' | '|' | '\\'
+            )
+            || (c == ':' && style == LanguageStyle::CommonLisp)
+            || (matches!(c, '{' | '}') && style == LanguageStyle::Racket);
+        if needs_escape {
+            return Some(format!("{c:?} at index {i} would need to be escaped"));
+        }
+        if c.is_uppercase() && style == LanguageStyle::CommonLisp {
+            return Some(format!(
+                "{c:?} at index {i} is upper case; the reader upcases unescaped names, so a name \
+                 is written in lower case for its upper case symbol and {c:?} would need escaping"
+            ));
+        }
+    }
+    None
+}
+
+fn equal_values(
+    lhs: &Value,
+    rhs: &Value,
+    visited: &mut HashSet<(*const Value, *const Value)>,
+) -> bool {
+    let mut all_equal = |lhs: &[Value], rhs: &[Value]| {
+        lhs.len() == rhs.len()
+            && lhs
+                .iter()
+                .zip(rhs.iter())
+                .all(|(lhs, rhs)| equal_values(lhs, rhs, visited))
+    };
+    match (lhs, rhs) {
+        (Value::Commented(lhs), _) => equal_values(lhs.value(), rhs, visited),
+        (_, Value::Commented(rhs)) => equal_values(lhs, rhs.value(), visited),
+        (Value::Shared(lhs_shared), Value::Shared(rhs_shared)) if lhs_shared.ptr_eq(rhs_shared) => {
+            true
+        }
+        (Value::Shared(_), _) | (_, Value::Shared(_)) => {
+            // a pair seen before is assumed equal, any difference is found along another path.
+            if !visited.insert((lhs, rhs)) {
+                return true;
+            }
+            match (unshare(lhs), unshare(rhs)) {
+                (Some(lhs), Some(rhs)) => equal_values(lhs, rhs, visited),
+                _ => false,
+            }
+        }
+        (Value::List(lhs), Value::List(rhs)) | (Value::Vector(lhs), Value::Vector(rhs)) => {
+            all_equal(lhs, rhs)
+        }
+        (Value::DottedList(lhs), Value::DottedList(rhs)) => {
+            all_equal(lhs.items(), rhs.items()) && equal_values(lhs.tail(), rhs.tail(), visited)
+        }
+        (Value::Map(lhs), Value::Map(rhs)) => {
+            // entries are matched by key, using the maps' test, regardless of their order.
+            lhs.test() == rhs.test()
+                && lhs.len() == rhs.len()
+                && lhs.entries().iter().all(|(key, lv)| match rhs.get(key) {
+                    Some(rv) => equal_values(lv, rv, visited),
+                    None => false,
+                })
+        }
+        (Value::Quoted(lhs), Value::Quoted(rhs)) => {
+            lhs.kind() == rhs.kind() && equal_values(lhs.datum(), rhs.datum(), visited)
+        }
+        (Value::Boxed(lhs), Value::Boxed(rhs)) => equal_values(lhs, rhs, visited),
+        (Value::Struct(lhs), Value::Struct(rhs)) => {
+            lhs.name() == rhs.name() && all_equal(lhs.fields(), rhs.fields())
+        }
+        (Value::Array(lhs), Value::Array(rhs)) => {
+            lhs.dimensions() == rhs.dimensions() && all_equal(lhs.elements(), rhs.elements())
+        }
+        (
+            Value::DatumLabel(DatumLabel::Define(lhs_label, lhs)),
+            Value::DatumLabel(DatumLabel::Define(rhs_label, rhs)),
+        ) => lhs_label == rhs_label && equal_values(lhs, rhs, visited),
+        _ => lhs == rhs,
+    }
+}
+
+fn association_index(items: &[Value], key: &Value) -> Option<usize> {
+    items.iter().position(|item| match item.resolved() {
+        Value::DottedList(pair) => pair.items.len() == 1 && pair.items[0].is_equal(key),
+        _ => false,
+    })
+}
+
+fn property_index(items: &[Value], key: &Keyword) -> Option<usize> {
+    items
+        .iter()
+        .step_by(2)
+        .position(|item| matches!(item.resolved(), Value::Keyword(k) if k == key))
+        .map(|index| index * 2 + 1)
+        .filter(|index| *index < items.len())
+}
+
+fn unshare(value: &Value) -> Option<&Value> {
+    match value {
+        Value::Shared(shared) => shared.get(),
+        _ => Some(value),
+    }
+}
+
+///
+/// Returns `true` if the reader for `style` would read `s` as a number. This covers integers,
+/// rationals, and decimals with the exponent markers of each style; the special values
+/// `+inf.0` and `+nan.0` and complex numbers, `1+2i` or `1@2`, in Racket and Scheme; and the
+/// special values `1.0e+INF` and `0.0e+NaN` in Emacs Lisp.
+///
+fn is_number_like(s: &str, style: LanguageStyle) -> bool {
+    match style {
+        LanguageStyle::Racket | LanguageStyle::Scheme => {
+            is_real_like(s, style) || is_complex_like(s, style)
+        }
+        LanguageStyle::EmacsLisp => {
+            is_real_like(s, style)
+                || s.strip_suffix("e+INF")
+                    .or_else(|| s.strip_suffix("e+NaN"))
+                    .map(|s| is_real_like(s, style))
+                    .unwrap_or_default()
+        }
+        _ => is_real_like(s, style),
+    }
+}
+
+fn is_real_like(s: &str, style: LanguageStyle) -> bool {
+    let is_integer = |s: &str| {
+        let digits = s.strip_prefix(['+', '-']).unwrap_or(s);
+        !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit())
+    };
+    let is_rational = || {
+        s.split_once('/')
+            .map(|(n, d)| is_integer(n) && !d.starts_with(['+', '-']) && is_integer(d))
+            .unwrap_or_default()
+    };
+    let is_decimal = || {
+        // the standard parser also accepts names such as `inf`, so a digit is required.
+        s.chars().any(|c| c.is_ascii_digit())
+            && s.replace(exponent_markers(style), "e")
+                .parse::<f64>()
+                .is_ok()
+    };
+    let is_special = || {
+        matches!(style, LanguageStyle::Racket | LanguageStyle::Scheme)
+            && ["+inf.", "-inf.", "+nan.", "-nan."].iter().any(|prefix| {
+                s.get(..prefix.len())
+                    .map(|p| p.eq_ignore_ascii_case(prefix))
+                    .unwrap_or_default()
+                    && match &s[prefix.len()..] {
+                        "0" => true,
+                        "f" | "t" => style == LanguageStyle::Racket,
+                        _ => false,
+                    }
+            })
+    };
+    is_integer(s) || is_rational() || is_decimal() || is_special()
+}
+
+fn is_complex_like(s: &str, style: LanguageStyle) -> bool {
+    if let Some((magnitude, angle)) = s.split_once('@') {
+        return is_real_like(magnitude, style) && is_real_like(angle, style);
+    }
+    let Some(rest) = s.strip_suffix(['i', 'I']) else {
+        return false;
+    };
+    let is_imaginary =
+        |s: &str| s.starts_with(['+', '-']) && (s.len() == 1 || is_real_like(s, style));
+    // the imaginary part starts at the last sign that does not follow an exponent marker.
+    let split = rest.char_indices().rev().find(|(i, c)| {
+        let before = &rest[..*i];
+        *i > 0
+            && matches!(c, '+' | '-')
+            && !(before.ends_with(exponent_markers(style))
+                && before[..before.len() - 1].ends_with(|c: char| c.is_ascii_digit() || c == '.'))
+    });
+    match split {
+        Some((i, _)) => is_real_like(&rest[..i], style) && is_imaginary(&rest[i..]),
+        None => is_imaginary(rest),
+    }
+}
+
+fn exponent_markers(style: LanguageStyle) -> &'static [char] {
+    match style {
+        LanguageStyle::Racket => &['e', 'E', 'd', 'D', 'f', 'F', 's', 'S', 'l', 'L', 't', 'T'],
+        LanguageStyle::CommonLisp => &['e', 'E', 'd', 'D', 'f', 'F', 's', 'S', 'l', 'L'],
+        _ => &['e', 'E'],
+    }
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
     }
 }
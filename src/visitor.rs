@@ -0,0 +1,222 @@
+/*!
+Provides the [`Visitor`] and [`VisitorMut`] traits, and the [`fold`] function, for traversing
+[`Value`] trees.
+
+Traversals are iterative, rather than recursive, so deeply nested values do not overflow the
+stack. The children of a value are those returned by [`Value::children`]; in particular shared
+values are not followed, as they may be cyclic.
+
+# Example
+
+```rust
+use sexpr_out::{sexpr, value::Symbol, visitor::{walk_mut, Visit, VisitorMut}, Value};
+
+struct Rename;
+
+impl VisitorMut for Rename {
+    fn enter(&mut self, value: &mut Value) -> Visit {
+        if value.as_symbol() == Some(&Symbol::new("old")) {
+            *value = Symbol::new("new").into();
+        }
+        Visit::Continue
+    }
+}
+
+let mut value = sexpr!((define (old x) (old (- x 1))));
+walk_mut(&mut value, &mut Rename);
+assert_eq!(value, sexpr!((define (new x) (new (- x 1)))));
+```
+
+ */
+
+/*
+Copyright 2024 Simon Johnston <johnstonskj@gmail.com>
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+use crate::value::Value;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Returned by visitor hooks to control the rest of the traversal.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Visit {
+    /// Continue the traversal.
+    #[default]
+    Continue,
+    /// Do not visit the children of the value just entered; its `leave` hook is still called.
+    /// Returned from `leave` this is the same as `Continue`.
+    SkipChildren,
+    /// End the traversal, no further hooks are called.
+    Stop,
+}
+
+///
+/// A visitor over a value tree. The `enter` hook is called for each value before its children
+/// (pre-order), and the `leave` hook after them (post-order).
+///
+pub trait Visitor {
+    fn enter(&mut self, _value: &Value) -> Visit {
+        Visit::Continue
+    }
+
+    fn leave(&mut self, _value: &Value) -> Visit {
+        Visit::Continue
+    }
+}
+
+///
+/// A visitor that may change the values in a tree. Changes made by `enter` are made before the
+/// value's children are visited, so replacing a list in `enter` means the new list's items are
+/// visited.
+///
+pub trait VisitorMut {
+    fn enter(&mut self, _value: &mut Value) -> Visit {
+        Visit::Continue
+    }
+
+    fn leave(&mut self, _value: &mut Value) -> Visit {
+        Visit::Continue
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Traverse `value` with `visitor`, returning [`Visit::Stop`] if a hook stopped the traversal
+/// and [`Visit::Continue`] otherwise.
+///
+pub fn walk<V>(value: &Value, visitor: &mut V) -> Visit
+where
+    V: Visitor + ?Sized,
+{
+    let mut stack = vec![Step::Enter(value)];
+    while let Some(step) = stack.pop() {
+        match step {
+            Step::Enter(value) => match visitor.enter(value) {
+                Visit::Continue => {
+                    stack.push(Step::Leave(value, ()));
+                    stack.extend(value.children().into_iter().rev().map(Step::Enter));
+                }
+                Visit::SkipChildren => stack.push(Step::Leave(value, ())),
+                Visit::Stop => return Visit::Stop,
+            },
+            Step::Leave(value, ()) => {
+                if visitor.leave(value) == Visit::Stop {
+                    return Visit::Stop;
+                }
+            }
+        }
+    }
+    Visit::Continue
+}
+
+///
+/// Traverse `value` with `visitor`, which may change it, returning [`Visit::Stop`] if a hook
+/// stopped the traversal and [`Visit::Continue`] otherwise. Changes made before the traversal
+/// was stopped are kept.
+///
+pub fn walk_mut<V>(value: &mut Value, visitor: &mut V) -> Visit
+where
+    V: VisitorMut + ?Sized,
+{
+    // Each value is moved out of its parent while it is visited, and moved back in once all of
+    // its children have been visited.
+    let mut stack = vec![Step::Enter(std::mem::replace(value, Value::empty_list()))];
+    let mut visited: Vec<Value> = Vec::default();
+    let mut stopped = false;
+    while let Some(step) = stack.pop() {
+        match step {
+            Step::Enter(mut value) if !stopped => match visitor.enter(&mut value) {
+                Visit::Continue => {
+                    let children = value.take_children();
+                    stack.push(Step::Leave(value, Some(children.len())));
+                    stack.extend(children.into_iter().rev().map(Step::Enter));
+                }
+                Visit::SkipChildren => stack.push(Step::Leave(value, None)),
+                Visit::Stop => {
+                    stopped = true;
+                    visited.push(value);
+                }
+            },
+            Step::Enter(value) => visited.push(value),
+            Step::Leave(mut value, children) => {
+                if let Some(count) = children {
+                    value.restore_children(visited.split_off(visited.len() - count));
+                }
+                if !stopped && visitor.leave(&mut value) == Visit::Stop {
+                    stopped = true;
+                }
+                visited.push(value);
+            }
+        }
+    }
+    *value = visited.pop().expect("no value visited");
+    if stopped {
+        Visit::Stop
+    } else {
+        Visit::Continue
+    }
+}
+
+///
+/// Fold `value` bottom-up, calling `f` for each value with the results already computed for
+/// its children, in the order returned by [`Value::children`].
+///
+/// ```rust
+/// use sexpr_out::{sexpr, visitor::fold};
+///
+/// let depth = fold(&sexpr!((a (b (c)) d)), |_, children: Vec<usize>| {
+///     children.into_iter().max().map(|max| max + 1).unwrap_or_default()
+/// });
+/// assert_eq!(depth, 3);
+/// ```
+///
+pub fn fold<T, F>(value: &Value, f: F) -> T
+where
+    F: FnMut(&Value, Vec<T>) -> T,
+{
+    let mut f = f;
+    let mut stack = vec![Step::Enter(value)];
+    let mut results: Vec<T> = Vec::default();
+    while let Some(step) = stack.pop() {
+        match step {
+            Step::Enter(value) => {
+                let children = value.children();
+                stack.push(Step::Leave(value, children.len()));
+                stack.extend(children.into_iter().rev().map(Step::Enter));
+            }
+            Step::Leave(value, count) => {
+                let children = results.split_off(results.len() - count);
+                results.push(f(value, children));
+            }
+        }
+    }
+    results.pop().expect("no value folded")
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+enum Step<V, L> {
+    Enter(V),
+    Leave(V, L),
+}
@@ -37,12 +37,18 @@ limitations under the License.
 */
 
 use crate::{
-    value::{Keyword, Number, Symbol},
+    error::unsupported_in_style,
+    value::{
+        symbol_part_escape_reason, Array, CharModifier, Comment, Commented, Complex, DatumLabel,
+        DottedList, Keyword, LineCommentLevel, Map, MapTest, ModifiedChar, Node, NodeKind, Number,
+        PropertizedString, QuoteKind, Quoted, Rational, Regexp, RegexpSyntax, Shared, Struct,
+        Symbol, SymbolQualifier,
+    },
     Error, Value,
 };
 use itertools::{Itertools, Position};
 use objio::{HasOptions, ObjectWriter};
-use std::io::Write;
+use std::{collections::HashMap, io::Write, path::PathBuf, sync::OnceLock};
 
 // ------------------------------------------------------------------------------------------------
 // Public Types
@@ -66,13 +72,53 @@ pub enum QuoteStyle {
     AsNeeded(bool),
 }
 
+///
+/// The representation used for maps in styles that have no map literal, Common Lisp and Scheme.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum MapFallback {
+    #[default]
+    AssociationList,
+    PropertyList,
+}
+
+///
+/// The representation used for rational numbers in Emacs Lisp, which has no rational type.
+///
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum RationalFallback {
+    #[default]
+    Flonum,
+    Error,
+}
+
+///
+/// The kind of definition form used when a value is wrapped in a definition. Racket and Scheme
+/// always use `define`; Common Lisp uses `defvar`, `defparameter`, or `defconstant`; and Emacs
+/// Lisp uses `defvar` for variables and parameters and `defconst` for constants.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum DefinitionKind {
+    #[default]
+    Variable,
+    Parameter,
+    Constant,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
 pub struct Options {
     line_width: usize,
     pair_kw_args: bool,
     wrap_in_define: bool,
+    definition_name: Option<String>,
+    definition_kind: DefinitionKind,
+    definition_documentation: Option<String>,
+    align_kw_args: bool,
     style: LanguageStyle,
     quote: QuoteStyle,
+    map_fallback: MapFallback,
+    rational_fallback: RationalFallback,
+    coerce_byte_vectors: bool,
 }
 
 #[derive(Debug, Default)]
@@ -86,32 +132,103 @@ pub struct Writer {
 // ------------------------------------------------------------------------------------------------
 
 pub(crate) trait Printable {
-    fn stringify(&self, style: LanguageStyle) -> String;
-    fn printed_length(&self, style: LanguageStyle) -> usize {
-        self.stringify(style).len()
+    fn stringify(&self, options: &Options) -> Result<String, Error>;
+    fn printed_length(&self, options: &Options) -> Result<usize, Error> {
+        Ok(self.stringify(options)?.len())
     }
 
-    fn print<W>(&self, w: &mut W, style: LanguageStyle) -> Result<(), Error>
+    fn print<W>(&self, w: &mut W, options: &Options) -> Result<(), Error>
     where
         W: Write,
     {
-        Ok(w.write_all(self.stringify(style).as_bytes())?)
+        Ok(w.write_all(self.stringify(options)?.as_bytes())?)
     }
 
     fn pretty_print<W>(
         &self,
         w: &mut W,
         _current_indentation: usize,
-        _line_width: usize,
-        style: LanguageStyle,
+        options: &Options,
     ) -> Result<(), Error>
     where
         W: Write,
     {
-        self.print(w, style)
+        self.print(w, options)
     }
 }
 
+///
+/// The written prefix of a quote-family form, either a short reader prefix or the name of the
+/// long-form operator.
+///
+#[derive(Clone, Copy, Debug)]
+enum QuotePrefix {
+    Short(&'static str),
+    Long(&'static str),
+}
+
+///
+/// A borrowed quote-family form, used both for [`Quoted`] values and to quote a top-level datum
+/// without copying it.
+///
+#[derive(Clone, Copy, Debug)]
+struct QuotedDatum<'a> {
+    kind: QuoteKind,
+    datum: &'a Value,
+}
+
+///
+/// A group of sequence items that the pretty printer will not break across lines; either a
+/// single item, or a keyword and the value that follows it. The key width is used to pad keywords
+/// so that the values of aligned pairs form a column.
+///
+#[derive(Clone, Copy, Debug)]
+struct ItemGroup<'a, 'b> {
+    items: &'b [SequenceItem<'a>],
+    key_width: usize,
+}
+
+///
+/// The value written by the writer, after applying the quote style and definition options.
+///
+#[derive(Debug)]
+enum TopLevel<'a> {
+    Datum(&'a Value),
+    Quoted(QuotedDatum<'a>),
+    Definition(Definition<'a>),
+    Commented(&'a Commented, Box<TopLevel<'a>>),
+}
+
+///
+/// A definition form wrapping the written value, such as `(define name 'value)`.
+///
+#[derive(Debug)]
+struct Definition<'a> {
+    operator: &'static str,
+    name: Symbol,
+    value: Box<TopLevel<'a>>,
+    documentation: Option<String>,
+}
+
+///
+/// Replaces shared values referenced more than once with datum labels. The first reference, in
+/// the order values are written, defines the label and later references refer to it.
+///
+#[derive(Debug, Default)]
+struct DatumLabeler {
+    counts: HashMap<*const OnceLock<Value>, usize>,
+    labels: HashMap<*const OnceLock<Value>, usize>,
+}
+
+///
+/// A single element of a list-like sequence; the tail of a dotted list is printed as `. tail`.
+///
+#[derive(Clone, Copy, Debug)]
+enum SequenceItem<'a> {
+    Value(&'a Value),
+    Tail(&'a Value),
+}
+
 // ------------------------------------------------------------------------------------------------
 // Private Values
 // ------------------------------------------------------------------------------------------------
@@ -119,23 +236,33 @@ pub(crate) trait Printable {
 /// List Delimiters
 const CHAR_LIST_OPEN: char = '(';
 const CHAR_LIST_CLOSE: char = ')';
-const LIST_OPEN: &[u8] = b"(";
-const LIST_CLOSE: &[u8] = b")";
-const EMPTY_LIST: &[u8] = b"()";
+const LIST_OPEN: &str = "(";
+const LIST_CLOSE: &str = ")";
 const CHAR_SQLIST_OPEN: char = '[';
 const CHAR_SQLIST_CLOSE: char = ']';
 
 /// Separators Separator
 const STR_EMPTY: &str = "";
 const SPACE: &[u8] = b" ";
+const CHAR_SPACE: char = ' ';
+const DOT_SPACE: &[u8] = b". ";
 const NEWLINE: &[u8] = b"\n";
 const CHAR_NEWLINE: char = '\n';
+const STR_NEWLINE: &str = "\n";
 
 /// Special Reader Syntax
 const CHAR_NUMBER_SIGN: char = '#';
 const CHAR_COLON: char = ':';
 const CHAR_SEMICOLON: char = ';';
 const CHAR_PERIOD: char = '.';
+const CHAR_SLASH: char = '/';
+const CHAR_ASTERISK: char = '*';
+const CHAR_DOUBLE_QUOTE: char = '"';
+const CHAR_IMAGINARY: char = 'i';
+const STR_PLUS: &str = "+";
+const STR_NAN: &str = "+nan.0";
+const STR_POSITIVE_INFINITY: &str = "+inf.0";
+const STR_NEGATIVE_INFINITY: &str = "-inf.0";
 
 /// Escaping
 const CHAR_BACKSLASH: char = '\\';
@@ -147,6 +274,17 @@ const CHAR_QUOTE: char = '\'';
 const CHAR_QUASI_QUOTE: char = '`';
 const CHAR_UNQUOTE: char = ',';
 const CHAR_OTHER_QUOTE: char = '‘';
+const STR_QUOTE: &str = "'";
+const STR_QUASI_QUOTE: &str = "`";
+const STR_UNQUOTE: &str = ",";
+const STR_UNQUOTE_SPLICING: &str = ",@";
+const QUOTE_LONG: &str = "quote";
+const QUASI_QUOTE_LONG: &str = "quasiquote";
+const UNQUOTE_LONG: &str = "unquote";
+const UNQUOTE_SPLICING_LONG: &str = "unquote-splicing";
+const ELISP_QUASI_QUOTE_LONG: &str = "\\`";
+const ELISP_UNQUOTE_LONG: &str = "\\,";
+const ELISP_UNQUOTE_SPLICING_LONG: &str = "\\,@";
 
 const KEYWORD_FALSE: &str = "f";
 const KEYWORD_FALSE_LONG: &str = "false";
@@ -154,7 +292,62 @@ const KEYWORD_TRUE: &str = "t";
 const KEYWORD_TRUE_LONG: &str = "true";
 const KEYWORD_NIL: &str = "nil";
 
+/// Definitions
+const LINE_COMMENT_MARGIN: &str = ";";
+const LINE_COMMENT_CODE: &str = ";;";
+const LINE_COMMENT_SECTION: &str = ";;;";
+const BLOCK_COMMENT_OPEN: &str = "#|";
+const BLOCK_COMMENT_CLOSE: &str = "|#";
+const DATUM_COMMENT: &str = "#;";
+
+const DATUM_LABEL_DEFINE: char = '=';
+const DATUM_LABEL_REFERENCE: char = '#';
+
+const DEFINE: &str = "define";
+const CLISP_DEFVAR: &str = "defvar";
+const CLISP_DEFPARAMETER: &str = "defparameter";
+const CLISP_DEFCONSTANT: &str = "defconstant";
+const ELISP_DEFVAR: &str = "defvar";
+const ELISP_DEFCONST: &str = "defconst";
+const DEFINITION_BODY_INDENT: usize = 2;
+
 const RACKET_SYMBOL_PREFIX: &str = "#%";
+const RACKET_HASH_EQUAL_OPEN: &str = "#hash(";
+const RACKET_HASH_EQV_OPEN: &str = "#hasheqv(";
+const RACKET_HASH_EQ_OPEN: &str = "#hasheq(";
+const RACKET_BOX_PREFIX: &str = "#&";
+const RACKET_PREFAB_OPEN: &str = "#s(";
+const RACKET_REGEXP_PREFIX: &str = "#rx";
+const RACKET_PREGEXP_PREFIX: &str = "#px";
+const RACKET_STRING_TO_PATH: &str = "string->path";
+const CLISP_PATHNAME_PREFIX: &str = "#P";
+const CLISP_BIT_VECTOR_PREFIX: &str = "#*";
+const CLISP_ARRAY_SUFFIX: &str = "A";
+const STR_FUNCTION: &str = "#'";
+const FUNCTION_LONG: &str = "function";
+const TREE_SITTER_MISSING: &str = "MISSING";
+const TREE_SITTER_ERROR: &str = "ERROR";
+const TREE_SITTER_FIELD_SEPARATOR: &str = ": ";
+const TREE_SITTER_CHILD_INDENT: usize = 2;
+const CLISP_COMPLEX_OPEN: &str = "#C(";
+const CLISP_COERCE_OPEN: &str = "(coerce ";
+const CLISP_OCTET_VECTOR_TYPE: &str = " '(vector (unsigned-byte 8)))";
+const SCHEME_BYTEVECTOR_OPEN: &str = "#u8(";
+const ELISP_RECORD_OPEN: &str = "#s(";
+const ELISP_BOOL_VECTOR_PREFIX: &str = "#&";
+const ELISP_PROPERTIZED_OPEN: &str = "#(";
+const ELISP_MODIFIER_ALT: &str = "\\A-";
+const ELISP_MODIFIER_CONTROL: &str = "\\C-";
+const ELISP_MODIFIER_HYPER: &str = "\\H-";
+const ELISP_MODIFIER_META: &str = "\\M-";
+const ELISP_MODIFIER_SHIFT: &str = "\\S-";
+const ELISP_MODIFIER_SUPER: &str = "\\s-";
+const ELISP_HASH_TABLE: &str = "hash-table";
+const ELISP_HASH_TEST: &str = "test";
+const ELISP_HASH_DATA: &str = "data";
+const ELISP_TEST_EQUAL: &str = "equal";
+const ELISP_TEST_EQL: &str = "eql";
+const ELISP_TEST_EQ: &str = "eq";
 const CHAR_PREFIX: &str = "#\\";
 const CHAR_PREFIX_UNICODE: &str = "#\\u";
 const CHAR_PREFIX_UNICODE_LONG: &str = "#\\U";
@@ -200,6 +393,44 @@ impl Options {
 
     // --------------------------------------------------------------------------------------------
 
+    pub fn with_pair_kw_args(self, pair_kw_args: bool) -> Self {
+        let mut self_mut = self;
+        self_mut.set_pair_kw_args(pair_kw_args);
+        self_mut
+    }
+
+    pub fn pair_kw_args(&self) -> &bool {
+        &self.pair_kw_args
+    }
+
+    ///
+    /// If set, the pretty printer keeps a keyword and the value following it on the same line,
+    /// breaking lines only between keyword/value pairs.
+    ///
+    pub fn set_pair_kw_args(&mut self, pair_kw_args: bool) {
+        self.pair_kw_args = pair_kw_args;
+    }
+
+    pub fn with_align_kw_args(self, align_kw_args: bool) -> Self {
+        let mut self_mut = self;
+        self_mut.set_align_kw_args(align_kw_args);
+        self_mut
+    }
+
+    pub fn align_kw_args(&self) -> &bool {
+        &self.align_kw_args
+    }
+
+    ///
+    /// If set, along with `pair_kw_args`, a list that does not fit on one line has each
+    /// keyword/value pair on its own line with the values aligned in a single column.
+    ///
+    pub fn set_align_kw_args(&mut self, align_kw_args: bool) {
+        self.align_kw_args = align_kw_args;
+    }
+
+    // --------------------------------------------------------------------------------------------
+
     pub fn with_style(self, style: LanguageStyle) -> Self {
         let mut self_mut = self;
         self_mut.set_style(style);
@@ -226,10 +457,76 @@ impl Options {
         &self.wrap_in_define
     }
 
+    ///
+    /// If set, the written value is wrapped in a definition form, such as `(define name 'value)`,
+    /// which requires that a definition name is also set.
+    ///
     pub fn set_wrap_in_define(&mut self, wrap_in_define: bool) {
         self.wrap_in_define = wrap_in_define;
     }
 
+    pub fn with_definition_name<S>(self, definition_name: S) -> Self
+    where
+        S: Into<String>,
+    {
+        let mut self_mut = self;
+        self_mut.set_definition_name(definition_name);
+        self_mut
+    }
+
+    pub fn definition_name(&self) -> Option<&String> {
+        self.definition_name.as_ref()
+    }
+
+    ///
+    /// Set the name bound by the definition form. In Common Lisp variables and parameters are
+    /// given the conventional `*earmuffs*` if not already present.
+    ///
+    pub fn set_definition_name<S>(&mut self, definition_name: S)
+    where
+        S: Into<String>,
+    {
+        self.definition_name = Some(definition_name.into());
+    }
+
+    pub fn with_definition_kind(self, definition_kind: DefinitionKind) -> Self {
+        let mut self_mut = self;
+        self_mut.set_definition_kind(definition_kind);
+        self_mut
+    }
+
+    pub fn definition_kind(&self) -> &DefinitionKind {
+        &self.definition_kind
+    }
+
+    pub fn set_definition_kind(&mut self, definition_kind: DefinitionKind) {
+        self.definition_kind = definition_kind;
+    }
+
+    pub fn with_definition_documentation<S>(self, definition_documentation: S) -> Self
+    where
+        S: Into<String>,
+    {
+        let mut self_mut = self;
+        self_mut.set_definition_documentation(definition_documentation);
+        self_mut
+    }
+
+    pub fn definition_documentation(&self) -> Option<&String> {
+        self.definition_documentation.as_ref()
+    }
+
+    ///
+    /// Set a documentation string for the definition form; this is only written in the Common
+    /// Lisp and Emacs Lisp styles as `define` has no documentation string.
+    ///
+    pub fn set_definition_documentation<S>(&mut self, definition_documentation: S)
+    where
+        S: Into<String>,
+    {
+        self.definition_documentation = Some(definition_documentation.into());
+    }
+
     // --------------------------------------------------------------------------------------------
 
     pub fn with_quote(self, quote: QuoteStyle) -> Self {
@@ -245,6 +542,58 @@ impl Options {
     pub fn set_quote(&mut self, quote: QuoteStyle) {
         self.quote = quote;
     }
+
+    // --------------------------------------------------------------------------------------------
+
+    pub fn with_map_fallback(self, map_fallback: MapFallback) -> Self {
+        let mut self_mut = self;
+        self_mut.set_map_fallback(map_fallback);
+        self_mut
+    }
+
+    pub fn map_fallback(&self) -> &MapFallback {
+        &self.map_fallback
+    }
+
+    pub fn set_map_fallback(&mut self, map_fallback: MapFallback) {
+        self.map_fallback = map_fallback;
+    }
+
+    // --------------------------------------------------------------------------------------------
+
+    pub fn with_rational_fallback(self, rational_fallback: RationalFallback) -> Self {
+        let mut self_mut = self;
+        self_mut.set_rational_fallback(rational_fallback);
+        self_mut
+    }
+
+    pub fn rational_fallback(&self) -> &RationalFallback {
+        &self.rational_fallback
+    }
+
+    pub fn set_rational_fallback(&mut self, rational_fallback: RationalFallback) {
+        self.rational_fallback = rational_fallback;
+    }
+
+    // --------------------------------------------------------------------------------------------
+
+    pub fn with_coerce_byte_vectors(self, coerce_byte_vectors: bool) -> Self {
+        let mut self_mut = self;
+        self_mut.set_coerce_byte_vectors(coerce_byte_vectors);
+        self_mut
+    }
+
+    pub fn coerce_byte_vectors(&self) -> &bool {
+        &self.coerce_byte_vectors
+    }
+
+    ///
+    /// If set, byte strings written in the Common Lisp style are wrapped as
+    /// `(coerce #(...) '(vector (unsigned-byte 8)))` to produce a specialized octet vector.
+    ///
+    pub fn set_coerce_byte_vectors(&mut self, coerce_byte_vectors: bool) {
+        self.coerce_byte_vectors = coerce_byte_vectors;
+    }
 }
 
 // ------------------------------------------------------------------------------------------------
@@ -268,12 +617,22 @@ impl ObjectWriter<Value> for Writer {
     where
         W: Write,
     {
+        let mut labeler = DatumLabeler::default();
+        labeler.count(object);
+        let labeled: Value;
+        let object = if labeler.counts.is_empty() {
+            object
+        } else {
+            labeled = labeler.label(object)?;
+            &labeled
+        };
+        let top_level = TopLevel::new(object, &self.options)?;
         if self.pretty_print {
-            object.pretty_print(w, 0, self.options.line_width, self.options.style)?;
+            top_level.pretty_print(w, 0, &self.options)?;
             w.write_all(NEWLINE)?;
             Ok(())
         } else {
-            object.print(w, self.options.style)
+            top_level.print(w, &self.options)
         }
     }
 }
@@ -301,8 +660,8 @@ impl Writer {
 macro_rules! display_to_printable {
     ($type:ty) => {
         impl Printable for $type {
-            fn stringify(&self, _: LanguageStyle) -> String {
-                self.to_string()
+            fn stringify(&self, _: &Options) -> Result<String, Error> {
+                Ok(self.to_string())
             }
         }
     };
@@ -312,6 +671,9 @@ macro_rules! display_to_printable {
 
 display_to_printable!(i64);
 
+#[cfg(feature = "bignum")]
+display_to_printable!(num_bigint::BigInt);
+
 // ------------------------------------------------------------------------------------------------
 
 display_to_printable!(f64);
@@ -319,10 +681,73 @@ display_to_printable!(f64);
 // ------------------------------------------------------------------------------------------------
 
 impl Printable for Number {
-    fn stringify(&self, style: LanguageStyle) -> String {
+    fn stringify(&self, options: &Options) -> Result<String, Error> {
         match self {
-            Number::Integer(v) => v.stringify(style),
-            Number::Flonum(v) => v.stringify(style),
+            Number::Integer(v) => v.stringify(options),
+            #[cfg(feature = "bignum")]
+            Number::BigInteger(v) => v.stringify(options),
+            Number::Rational(v) => v.stringify(options),
+            Number::Flonum(v) => v.stringify(options),
+            Number::Complex(v) => v.stringify(options),
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Printable for Rational {
+    fn stringify(&self, options: &Options) -> Result<String, Error> {
+        if self.is_integer() {
+            return self.numerator().stringify(options);
+        }
+        match (options.style(), options.rational_fallback()) {
+            (LanguageStyle::EmacsLisp, RationalFallback::Flonum) => {
+                self.to_flonum().stringify(options)
+            }
+            (LanguageStyle::EmacsLisp, RationalFallback::Error) => {
+                Err(unsupported_in_style("rational", *options.style()))
+            }
+            _ => Ok(format!(
+                "{}{CHAR_SLASH}{}",
+                self.numerator(),
+                self.denominator()
+            )),
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Complex numbers are written as `1.0+2.0i` in Racket and Scheme and as `#C(1.0 2.0)` in
+/// Common Lisp; Emacs Lisp and tree-sitter have no complex number syntax. The parts are inexact
+/// so they are always written with a decimal point, or exponent. Racket and Scheme write
+/// non-finite parts as `+nan.0`, `+inf.0`, or `-inf.0`, Common Lisp has no syntax for them.
+///
+impl Printable for Complex {
+    fn stringify(&self, options: &Options) -> Result<String, Error> {
+        let style = *options.style();
+        match style {
+            LanguageStyle::Racket | LanguageStyle::Scheme => {
+                let imaginary = complex_part(self.imaginary(), style)?;
+                Ok(format!(
+                    "{}{}{imaginary}{CHAR_IMAGINARY}",
+                    complex_part(self.real(), style)?,
+                    if imaginary.starts_with(['+', '-']) {
+                        STR_EMPTY
+                    } else {
+                        STR_PLUS
+                    }
+                ))
+            }
+            LanguageStyle::CommonLisp => Ok(format!(
+                "{CLISP_COMPLEX_OPEN}{} {}{CHAR_LIST_CLOSE}",
+                complex_part(self.real(), style)?,
+                complex_part(self.imaginary(), style)?
+            )),
+            LanguageStyle::EmacsLisp | LanguageStyle::TreeSitter => {
+                Err(unsupported_in_style("complex", *options.style()))
+            }
         }
     }
 }
@@ -347,92 +772,82 @@ impl Printable for Number {
 ///
 /// A #% also starts a symbol. (From https://docs.racket-lang.org/reference/reader.html#%28part._parse-symbol%29)
 ///
+///
+/// Each part of a qualified symbol is escaped separately. Package-qualified symbols are only
+/// supported by Common Lisp, uninterned symbols by Common Lisp and Emacs Lisp, and namespaced
+/// symbols by Racket, Scheme, and Emacs Lisp.
+///
 impl Printable for Symbol {
-    fn stringify(&self, style: LanguageStyle) -> String {
-        fn inner(s: &str, style: LanguageStyle) -> String {
-            let mut add_multi_escape = false;
-            let new_s = s
-                .chars()
-                .tuple_windows()
-                .map(|(c0, c1)| {
-                    if c1 == CHAR_VERTICAL_BAR && c0 != CHAR_BACKSLASH {
-                        add_multi_escape = true;
-                        format!("{c0}{CHAR_BACKSLASH}{CHAR_VERTICAL_BAR}")
-                    } else if is_char_nonprintable(c0) {
-                        add_multi_escape = true;
-                        format!("{}{c1}", string_escape_char(c0, style))
-                    } else {
-                        format!("{c0}{c1}")
-                    }
-                })
-                .collect::<String>();
-            if add_multi_escape || new_s != s {
-                format!("{CHAR_VERTICAL_BAR}{new_s}{CHAR_VERTICAL_BAR}")
-            } else {
-                new_s
-            }
-        }
-        if style == LanguageStyle::TreeSitter {
-            // a shame...
-            self.inner()
-        } else {
-            let s = self.as_ref();
-            if style == LanguageStyle::Racket && s.starts_with(RACKET_SYMBOL_PREFIX) {
-                inner(&s[2..], style)
-            } else if s.starts_with(CHAR_VERTICAL_BAR) && s.ends_with(CHAR_VERTICAL_BAR) {
-                let len = s.len() - 2;
-                inner(&s[1..len], style)
-            } else {
-                inner(s, style)
-            }
+    fn stringify(&self, options: &Options) -> Result<String, Error> {
+        let style = *options.style();
+        let name = escape_symbol_part(self.as_ref(), style);
+        match (self.qualifier(), style) {
+            (None, _) => Ok(name),
+            (
+                Some(SymbolQualifier::Namespace(namespace)),
+                LanguageStyle::Racket | LanguageStyle::Scheme | LanguageStyle::EmacsLisp,
+            ) => Ok(format!(
+                "{}{CHAR_SLASH}{name}",
+                escape_symbol_part(namespace, style)
+            )),
+            (Some(SymbolQualifier::External(package)), LanguageStyle::CommonLisp) => Ok(format!(
+                "{}{CHAR_COLON}{name}",
+                escape_symbol_part(package, style)
+            )),
+            (Some(SymbolQualifier::Internal(package)), LanguageStyle::CommonLisp) => Ok(format!(
+                "{}{CHAR_COLON}{CHAR_COLON}{name}",
+                escape_symbol_part(package, style)
+            )),
+            (
+                Some(SymbolQualifier::Uninterned),
+                LanguageStyle::CommonLisp | LanguageStyle::EmacsLisp,
+            ) => Ok(format!("{CHAR_NUMBER_SIGN}{CHAR_COLON}{name}")),
+            (Some(_), _) => Err(unsupported_in_style("qualified symbol", style)),
         }
     }
 
-    fn printed_length(&self, style: LanguageStyle) -> usize {
-        self.stringify(style).len()
-    }
-
-    fn print<W>(&self, w: &mut W, style: LanguageStyle) -> Result<(), Error>
+    fn print<W>(&self, w: &mut W, options: &Options) -> Result<(), Error>
     where
         W: Write,
     {
-        Ok(w.write_all(self.stringify(style).as_bytes())?)
+        Ok(w.write_all(self.stringify(options)?.as_bytes())?)
     }
 
     fn pretty_print<W>(
         &self,
         w: &mut W,
         _current_indentation: usize,
-        _line_width: usize,
-        style: LanguageStyle,
+        options: &Options,
     ) -> Result<(), Error>
     where
         W: Write,
     {
-        self.print(w, style)
+        self.print(w, options)
     }
 }
 
 // ------------------------------------------------------------------------------------------------
 
 impl Printable for Keyword {
-    fn stringify(&self, style: LanguageStyle) -> String {
-        let inner = self.inner().stringify(style);
-        match style {
+    fn stringify(&self, options: &Options) -> Result<String, Error> {
+        let style = *options.style();
+        let inner = self.inner().stringify(options)?;
+        Ok(match style {
             LanguageStyle::Racket => format!("{CHAR_NUMBER_SIGN}{CHAR_COLON}{inner}"),
             LanguageStyle::TreeSitter => format!("{inner}{CHAR_COLON}"),
             LanguageStyle::CommonLisp => format!("{CHAR_COLON}{inner}"),
             LanguageStyle::Scheme => format!("{CHAR_COLON}{inner}"),
             LanguageStyle::EmacsLisp => format!("{CHAR_COLON}{inner}"),
-        }
+        })
     }
 }
 
 // ------------------------------------------------------------------------------------------------
 
 impl Printable for bool {
-    fn stringify(&self, style: LanguageStyle) -> String {
-        match (style, *self) {
+    fn stringify(&self, options: &Options) -> Result<String, Error> {
+        let style = *options.style();
+        Ok(match (style, *self) {
             (LanguageStyle::Racket, true) => format!("{CHAR_NUMBER_SIGN}{KEYWORD_TRUE}"),
             (LanguageStyle::Racket, false) => format!("{CHAR_NUMBER_SIGN}{KEYWORD_FALSE}"),
             (LanguageStyle::TreeSitter, true) => KEYWORD_TRUE_LONG.to_string(),
@@ -443,14 +858,14 @@ impl Printable for bool {
             (LanguageStyle::Scheme, false) => format!("{CHAR_NUMBER_SIGN}{KEYWORD_FALSE}"),
             (LanguageStyle::EmacsLisp, true) => KEYWORD_TRUE.to_string(),
             (LanguageStyle::EmacsLisp, false) => KEYWORD_NIL.to_string(),
-        }
+        })
     }
 }
 
 // ------------------------------------------------------------------------------------------------
 
 impl Printable for char {
-    fn stringify(&self, style: LanguageStyle) -> String {
+    fn stringify(&self, options: &Options) -> Result<String, Error> {
         fn escape(
             c: char,
             std_prefix: &str,
@@ -472,7 +887,8 @@ impl Printable for char {
                 _ => format!("{std_prefix}{c}"),
             }
         }
-        match (*self, style) {
+        let style = *options.style();
+        Ok(match (*self, style) {
             ('\u{00}', LanguageStyle::Racket) => format!("{CHAR_PREFIX}null"),
             ('\u{08}', LanguageStyle::Racket) => {
                 format!("{CHAR_PREFIX}backspace")
@@ -541,15 +957,49 @@ impl Printable for char {
                 ELISP_CHAR_PREFIX_UNICODE,
                 Some(ELISP_CHAR_PREFIX_UNICODE_LONG),
             ),
+        })
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Characters with modifiers are written as `?\C-a`, with one prefix for each modifier, in Emacs
+/// Lisp; the character itself is escaped as for any other character.
+///
+impl Printable for ModifiedChar {
+    fn stringify(&self, options: &Options) -> Result<String, Error> {
+        let style = *options.style();
+        if style != LanguageStyle::EmacsLisp {
+            return Err(unsupported_in_style("modified character", style));
         }
+        let character = self.character().stringify(options)?;
+        Ok(format!(
+            "{ELISP_CHAR_PREFIX}{}{}",
+            self.modifiers()
+                .iter()
+                .map(|modifier| match modifier {
+                    CharModifier::Alt => ELISP_MODIFIER_ALT,
+                    CharModifier::Control => ELISP_MODIFIER_CONTROL,
+                    CharModifier::Hyper => ELISP_MODIFIER_HYPER,
+                    CharModifier::Meta => ELISP_MODIFIER_META,
+                    CharModifier::Shift => ELISP_MODIFIER_SHIFT,
+                    CharModifier::Super => ELISP_MODIFIER_SUPER,
+                })
+                .collect::<String>(),
+            character
+                .strip_prefix(ELISP_CHAR_PREFIX)
+                .unwrap_or(&character)
+        ))
     }
 }
 
 // ------------------------------------------------------------------------------------------------
 
 impl Printable for String {
-    fn stringify(&self, style: LanguageStyle) -> String {
-        format!(
+    fn stringify(&self, options: &Options) -> Result<String, Error> {
+        let style = *options.style();
+        Ok(format!(
             "{:?}",
             if self.len() == 1 {
                 let c = self.chars().next().unwrap();
@@ -597,133 +1047,1119 @@ impl Printable for String {
                     })
                     .collect::<String>()
             }
-        )
+        ))
     }
 }
 
 // ------------------------------------------------------------------------------------------------
 
-impl Printable for Vec<Value> {
-    fn stringify(&self, style: LanguageStyle) -> String {
-        format!(
-            "{}{}{}",
-            CHAR_LIST_OPEN,
-            self.iter()
-                .map(|v| v.stringify(style))
-                .collect::<Vec<String>>()
-                .join(" "),
-            CHAR_LIST_CLOSE,
-        )
-    }
-    fn printed_length(&self, style: LanguageStyle) -> usize {
-        self.iter().fold(0, |t, v| t + v.printed_length(style))
-        // add inter-datum spaces
-            + if self.len() < 2 { 0 } else { self.len() - 1 }
+///
+/// Byte strings are written as `#"..."` in Racket, `#u8(...)` in Scheme, `#(...)` in Common Lisp,
+/// and as a unibyte string, with octal escapes for non-ASCII bytes, in Emacs Lisp.
+///
+impl Printable for Vec<u8> {
+    fn stringify(&self, options: &Options) -> Result<String, Error> {
+        match options.style() {
+            LanguageStyle::Racket => Ok(format!(
+                "{CHAR_NUMBER_SIGN}{CHAR_DOUBLE_QUOTE}{}{CHAR_DOUBLE_QUOTE}",
+                escape_bytes(self)
+            )),
+            LanguageStyle::EmacsLisp => Ok(format!(
+                "{CHAR_DOUBLE_QUOTE}{}{CHAR_DOUBLE_QUOTE}",
+                escape_bytes(self)
+            )),
+            LanguageStyle::Scheme => stringify_sequence(
+                SCHEME_BYTEVECTOR_OPEN,
+                &SequenceItem::list(&byte_values(self)),
+                LIST_CLOSE,
+                options,
+            ),
+            LanguageStyle::CommonLisp => {
+                let (open, close) = vector_delimiters(*options.style())?;
+                let vector = stringify_sequence(
+                    &open,
+                    &SequenceItem::list(&byte_values(self)),
+                    &close,
+                    options,
+                )?;
+                if *options.coerce_byte_vectors() {
+                    Ok(format!(
+                        "{CLISP_COERCE_OPEN}{vector}{CLISP_OCTET_VECTOR_TYPE}"
+                    ))
+                } else {
+                    Ok(vector)
+                }
+            }
+            LanguageStyle::TreeSitter => Err(unsupported_in_style("bytes", *options.style())),
+        }
     }
-    fn print<W>(&self, w: &mut W, style: LanguageStyle) -> Result<(), crate::Error>
+    fn pretty_print<W>(
+        &self,
+        w: &mut W,
+        current_indentation: usize,
+        options: &Options,
+    ) -> Result<(), Error>
     where
-        W: std::io::Write,
+        W: Write,
     {
-        if self.is_empty() {
-            w.write_all(EMPTY_LIST)?;
-        } else {
-            w.write_all(LIST_OPEN)?;
-            for (value, is_last) in self
-                .iter()
-                .enumerate()
-                .map(|(i, v)| (v, i == self.len() - 1))
-            {
-                value.print(w, style)?;
-                if !is_last {
-                    w.write_all(SPACE)?;
-                }
+        match options.style() {
+            LanguageStyle::Scheme => pretty_print_sequence(
+                w,
+                SCHEME_BYTEVECTOR_OPEN,
+                &SequenceItem::list(&byte_values(self)),
+                LIST_CLOSE,
+                current_indentation,
+                options,
+            ),
+            LanguageStyle::CommonLisp if !options.coerce_byte_vectors() => {
+                let (open, close) = vector_delimiters(*options.style())?;
+                pretty_print_sequence(
+                    w,
+                    &open,
+                    &SequenceItem::list(&byte_values(self)),
+                    &close,
+                    current_indentation,
+                    options,
+                )
             }
-            w.write_all(LIST_CLOSE)?;
+            _ => self.print(w, options),
         }
-        Ok(())
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Printable for Struct {
+    fn stringify(&self, options: &Options) -> Result<String, Error> {
+        stringify_sequence(
+            struct_open(options)?,
+            &SequenceItem::list(&struct_values(self)),
+            LIST_CLOSE,
+            options,
+        )
     }
     fn pretty_print<W>(
         &self,
         w: &mut W,
         current_indentation: usize,
-        line_width: usize,
-        style: LanguageStyle,
-    ) -> Result<(), crate::Error>
+        options: &Options,
+    ) -> Result<(), Error>
     where
-        W: std::io::Write,
+        W: Write,
     {
-        let print_width = self.printed_length(style);
-        if self.is_empty() {
-            w.write_all(EMPTY_LIST)?;
-        } else if current_indentation + print_width < line_width {
-            self.print(w, style)?;
+        pretty_print_sequence(
+            w,
+            struct_open(options)?,
+            &SequenceItem::list(&struct_values(self)),
+            LIST_CLOSE,
+            current_indentation,
+            options,
+        )
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Regular expressions are written as `#rx"..."` or `#px"..."` in Racket, with a byte string
+/// pattern, `#rx#"..."`, for byte regexps.
+///
+impl Printable for Regexp {
+    fn stringify(&self, options: &Options) -> Result<String, Error> {
+        let style = *options.style();
+        if style != LanguageStyle::Racket {
+            return Err(unsupported_in_style("regexp", style));
+        }
+        let prefix = match self.syntax() {
+            RegexpSyntax::Regexp => RACKET_REGEXP_PREFIX,
+            RegexpSyntax::Perl => RACKET_PREGEXP_PREFIX,
+        };
+        let pattern = if self.is_byte_regexp() {
+            self.pattern().as_bytes().to_vec().stringify(options)?
         } else {
-            let current_indentation = current_indentation + 1; // one '('
-            let mut current_width = current_indentation;
-            w.write_all(LIST_OPEN)?;
-            let last_value_index: usize = self.len() - 1;
-            for (i, v) in self.iter().enumerate() {
-                current_width += v.printed_length(style);
-                v.pretty_print(w, current_indentation, line_width, style)?;
-                if i < last_value_index {
-                    let next_width = self.get(i + 1).unwrap().printed_length(style);
-                    if (current_width + next_width + 1) >= line_width {
-                        newline_and_indent(current_indentation, w)?;
-                        current_width = current_indentation;
-                    } else {
-                        w.write_all(SPACE)?;
-                    }
-                }
-            }
-            w.write_all(LIST_CLOSE)?;
+            string_literal(self.pattern())
+        };
+        Ok(format!("{prefix}{pattern}"))
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Racket paths have no readable literal form, `#<path:...>`, and so are written as a call to
+/// the `string->path` constructor. As this form is only a path when evaluated it is an error to
+/// write a path within quoted data in Racket. Common Lisp pathnames are written as `#P"..."`.
+///
+impl Printable for PathBuf {
+    fn stringify(&self, options: &Options) -> Result<String, Error> {
+        let style = *options.style();
+        let path = string_literal(&self.to_string_lossy());
+        match style {
+            LanguageStyle::Racket => Ok(format!(
+                "{CHAR_LIST_OPEN}{RACKET_STRING_TO_PATH} {path}{CHAR_LIST_CLOSE}"
+            )),
+            LanguageStyle::CommonLisp => Ok(format!("{CLISP_PATHNAME_PREFIX}{path}")),
+            _ => Err(unsupported_in_style("path", style)),
         }
-        Ok(())
     }
 }
 
 // ------------------------------------------------------------------------------------------------
 
-impl Printable for Value {
-    fn stringify(&self, style: LanguageStyle) -> String {
-        match self {
-            Value::Bool(v) => v.stringify(style),
-            Value::Number(v) => v.stringify(style),
-            Value::Character(v) => v.stringify(style),
-            Value::String(v) => v.stringify(style),
-            Value::Symbol(v) => v.stringify(style),
-            Value::Keyword(v) => v.stringify(style),
-            Value::List(v) => v.stringify(style),
+///
+/// Bit vectors are written as `#*1011` in Common Lisp. In Emacs Lisp they are written as
+/// bool-vectors, `#&5"\37"`, with the length followed by a unibyte string of the bits packed
+/// into bytes with the first bit as the least significant bit of the first byte.
+///
+impl Printable for Vec<bool> {
+    fn stringify(&self, options: &Options) -> Result<String, Error> {
+        match options.style() {
+            LanguageStyle::CommonLisp => Ok(format!(
+                "{CLISP_BIT_VECTOR_PREFIX}{}",
+                self.iter()
+                    .map(|bit| if *bit { '1' } else { '0' })
+                    .collect::<String>()
+            )),
+            LanguageStyle::EmacsLisp => {
+                let bytes: Vec<u8> = self
+                    .chunks(8)
+                    .map(|bits| {
+                        bits.iter()
+                            .enumerate()
+                            .filter(|(_, bit)| **bit)
+                            .fold(0, |byte, (i, _)| byte | (1 << i))
+                    })
+                    .collect();
+                Ok(format!(
+                    "{ELISP_BOOL_VECTOR_PREFIX}{}{CHAR_DOUBLE_QUOTE}{}{CHAR_DOUBLE_QUOTE}",
+                    self.len(),
+                    escape_bytes(&bytes)
+                ))
+            }
+            style => Err(unsupported_in_style("bit vector", *style)),
         }
     }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Arrays are written in Common Lisp as `#nA` followed by the elements nested as lists, one
+/// level for each dimension, so that a 2x2 array is written as `#2A((1 2) (3 4))`.
+///
+impl Printable for Array {
+    fn stringify(&self, options: &Options) -> Result<String, Error> {
+        Ok(format!(
+            "{}{}",
+            array_prefix(self, options)?,
+            nested_array_elements(self.dimensions(), self.elements()).stringify(options)?
+        ))
+    }
     fn pretty_print<W>(
         &self,
         w: &mut W,
         current_indentation: usize,
-        line_width: usize,
-        style: LanguageStyle,
+        options: &Options,
     ) -> Result<(), Error>
     where
         W: Write,
     {
-        match self {
-            Value::Bool(v) => v.pretty_print(w, current_indentation, line_width, style),
-            Value::Number(v) => v.pretty_print(w, current_indentation, line_width, style),
-            Value::Character(v) => v.pretty_print(w, current_indentation, line_width, style),
-            Value::String(v) => v.pretty_print(w, current_indentation, line_width, style),
-            Value::Symbol(v) => v.pretty_print(w, current_indentation, line_width, style),
-            Value::Keyword(v) => v.pretty_print(w, current_indentation, line_width, style),
-            Value::List(v) => v.pretty_print(w, current_indentation, line_width, style),
-        }
+        let prefix = array_prefix(self, options)?;
+        w.write_all(prefix.as_bytes())?;
+        nested_array_elements(self.dimensions(), self.elements()).pretty_print(
+            w,
+            current_indentation + prefix.len(),
+            options,
+        )
     }
 }
 
-// ------------------------------------------------------------------------------------------------
-// Private Functions
 // ------------------------------------------------------------------------------------------------
 
-#[inline(always)]
-fn is_char_nonprintable(c: char) -> bool {
-    matches!(c,
+///
+/// Strings with text properties are written as `#("text" start end (properties) ...)` in Emacs
+/// Lisp.
+///
+impl Printable for PropertizedString {
+    fn stringify(&self, options: &Options) -> Result<String, Error> {
+        stringify_sequence(
+            propertized_string_open(options)?,
+            &SequenceItem::list(&propertized_string_values(self)),
+            LIST_CLOSE,
+            options,
+        )
+    }
+    fn pretty_print<W>(
+        &self,
+        w: &mut W,
+        current_indentation: usize,
+        options: &Options,
+    ) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        pretty_print_sequence(
+            w,
+            propertized_string_open(options)?,
+            &SequenceItem::list(&propertized_string_values(self)),
+            LIST_CLOSE,
+            current_indentation,
+            options,
+        )
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Nodes are written as by `tree-sitter parse`; when pretty printed each child is on its own line
+/// indented two spaces from its parent, regardless of the line width.
+///
+impl Printable for Node {
+    fn stringify(&self, options: &Options) -> Result<String, Error> {
+        let mut result = node_head(self, options)?;
+        if let Some(close) = node_close(self) {
+            for child in self.children() {
+                result.push(CHAR_SPACE);
+                result.push_str(&child.stringify(options)?);
+            }
+            result.push_str(close);
+        }
+        Ok(result)
+    }
+    fn pretty_print<W>(
+        &self,
+        w: &mut W,
+        current_indentation: usize,
+        options: &Options,
+    ) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        w.write_all(node_head(self, options)?.as_bytes())?;
+        if let Some(close) = node_close(self) {
+            let child_indentation = current_indentation + TREE_SITTER_CHILD_INDENT;
+            for child in self.children() {
+                newline_and_indent(child_indentation, w)?;
+                child.pretty_print(w, child_indentation, options)?;
+            }
+            w.write_all(close.as_bytes())?;
+        }
+        Ok(())
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Printable for Vec<Value> {
+    fn stringify(&self, options: &Options) -> Result<String, Error> {
+        stringify_sequence(LIST_OPEN, &SequenceItem::list(self), LIST_CLOSE, options)
+    }
+    fn printed_length(&self, options: &Options) -> Result<usize, Error> {
+        sequence_printed_length(&SequenceItem::list(self), options)
+    }
+    fn print<W>(&self, w: &mut W, options: &Options) -> Result<(), crate::Error>
+    where
+        W: std::io::Write,
+    {
+        print_sequence(w, LIST_OPEN, &SequenceItem::list(self), LIST_CLOSE, options)
+    }
+    fn pretty_print<W>(
+        &self,
+        w: &mut W,
+        current_indentation: usize,
+        options: &Options,
+    ) -> Result<(), crate::Error>
+    where
+        W: std::io::Write,
+    {
+        pretty_print_sequence(
+            w,
+            LIST_OPEN,
+            &SequenceItem::list(self),
+            LIST_CLOSE,
+            current_indentation,
+            options,
+        )
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Dotted lists are normalized before printing, so that `(1 . (2 . (3)))` is written as the
+/// proper list `(1 2 3)` and `(1 . (2 . 3))` as `(1 2 . 3)`. Only lists that remain improper
+/// after normalization are an error for [`LanguageStyle::TreeSitter`].
+///
+impl Printable for DottedList {
+    fn stringify(&self, options: &Options) -> Result<String, Error> {
+        stringify_sequence(
+            LIST_OPEN,
+            &SequenceItem::dotted_list(self, options)?,
+            LIST_CLOSE,
+            options,
+        )
+    }
+    fn printed_length(&self, options: &Options) -> Result<usize, Error> {
+        sequence_printed_length(&SequenceItem::dotted_list(self, options)?, options)
+    }
+    fn print<W>(&self, w: &mut W, options: &Options) -> Result<(), crate::Error>
+    where
+        W: std::io::Write,
+    {
+        print_sequence(
+            w,
+            LIST_OPEN,
+            &SequenceItem::dotted_list(self, options)?,
+            LIST_CLOSE,
+            options,
+        )
+    }
+    fn pretty_print<W>(
+        &self,
+        w: &mut W,
+        current_indentation: usize,
+        options: &Options,
+    ) -> Result<(), crate::Error>
+    where
+        W: std::io::Write,
+    {
+        pretty_print_sequence(
+            w,
+            LIST_OPEN,
+            &SequenceItem::dotted_list(self, options)?,
+            LIST_CLOSE,
+            current_indentation,
+            options,
+        )
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Maps are written as `#hash((k . v) ...)` in Racket and `#s(hash-table test equal data (k v
+/// ...))` in Emacs Lisp. Common Lisp and Scheme have no map literal and so use the
+/// [`MapFallback`] from the options; tree-sitter cannot represent maps at all.
+///
+impl Printable for Map {
+    fn stringify(&self, options: &Options) -> Result<String, Error> {
+        let (open, values) = map_sequence(self, options)?;
+        stringify_sequence(open, &SequenceItem::list(&values), LIST_CLOSE, options)
+    }
+    fn print<W>(&self, w: &mut W, options: &Options) -> Result<(), crate::Error>
+    where
+        W: std::io::Write,
+    {
+        let (open, values) = map_sequence(self, options)?;
+        print_sequence(w, open, &SequenceItem::list(&values), LIST_CLOSE, options)
+    }
+    fn pretty_print<W>(
+        &self,
+        w: &mut W,
+        current_indentation: usize,
+        options: &Options,
+    ) -> Result<(), crate::Error>
+    where
+        W: std::io::Write,
+    {
+        let (open, values) = map_sequence(self, options)?;
+        pretty_print_sequence(
+            w,
+            open,
+            &SequenceItem::list(&values),
+            LIST_CLOSE,
+            current_indentation,
+            options,
+        )
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Printable for Quoted {
+    fn stringify(&self, options: &Options) -> Result<String, Error> {
+        QuotedDatum::new(self.kind(), self.datum()).stringify(options)
+    }
+    fn pretty_print<W>(
+        &self,
+        w: &mut W,
+        current_indentation: usize,
+        options: &Options,
+    ) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        QuotedDatum::new(self.kind(), self.datum()).pretty_print(w, current_indentation, options)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Quote-family forms are written in short form, `'x`, unless the options' quote style asks for
+/// the long form, `(quote x)`. Common Lisp has no standard long form for the backquote family and
+/// so these always use the short form.
+///
+impl Printable for QuotedDatum<'_> {
+    fn stringify(&self, options: &Options) -> Result<String, Error> {
+        let datum = self.datum.stringify(options)?;
+        Ok(match quote_prefix(self.kind, options)? {
+            QuotePrefix::Short(prefix) => format!("{prefix}{datum}"),
+            QuotePrefix::Long(name) => format!("{CHAR_LIST_OPEN}{name} {datum}{CHAR_LIST_CLOSE}"),
+        })
+    }
+    fn pretty_print<W>(
+        &self,
+        w: &mut W,
+        current_indentation: usize,
+        options: &Options,
+    ) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        match quote_prefix(self.kind, options)? {
+            QuotePrefix::Short(prefix) => {
+                w.write_all(prefix.as_bytes())?;
+                self.datum
+                    .pretty_print(w, current_indentation + prefix.len(), options)
+            }
+            QuotePrefix::Long(name) => {
+                let open = format!("{CHAR_LIST_OPEN}{name} ");
+                w.write_all(open.as_bytes())?;
+                self.datum
+                    .pretty_print(w, current_indentation + open.len(), options)?;
+                if ends_with_line_comment(self.datum, options) {
+                    newline_and_indent(current_indentation + open.len(), w)?;
+                }
+                w.write_all(LIST_CLOSE.as_bytes())?;
+                Ok(())
+            }
+        }
+    }
+}
+
+impl<'a> QuotedDatum<'a> {
+    fn new(kind: QuoteKind, datum: &'a Value) -> Self {
+        Self { kind, datum }
+    }
+
+    fn ends_with_line_comment(&self, options: &Options) -> bool {
+        matches!(quote_prefix(self.kind, options), Ok(QuotePrefix::Short(_)))
+            && ends_with_line_comment(self.datum, options)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Line comments are written with one, two, or three semicolons and a multi-line comment is
+/// written as consecutive line comments. Block comments are not supported by Emacs Lisp and datum
+/// comments are only supported by Racket and Scheme.
+///
+impl Printable for Comment {
+    fn stringify(&self, options: &Options) -> Result<String, Error> {
+        Ok(comment_lines(self, options)?.join(STR_NEWLINE))
+    }
+    fn pretty_print<W>(
+        &self,
+        w: &mut W,
+        current_indentation: usize,
+        options: &Options,
+    ) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        for (position, line) in comment_lines(self, options)?.iter().with_position() {
+            if matches!(position, Position::Middle | Position::Last) {
+                newline_and_indent(current_indentation, w)?;
+            }
+            w.write_all(line.as_bytes())?;
+        }
+        Ok(())
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Printable for Commented {
+    fn stringify(&self, options: &Options) -> Result<String, Error> {
+        stringify_commented(self, self.value(), options)
+    }
+    fn pretty_print<W>(
+        &self,
+        w: &mut W,
+        current_indentation: usize,
+        options: &Options,
+    ) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        pretty_print_commented(w, self, self.value(), current_indentation, None, options)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Printable for TopLevel<'_> {
+    fn stringify(&self, options: &Options) -> Result<String, Error> {
+        match self {
+            TopLevel::Datum(v) => v.stringify(options),
+            TopLevel::Quoted(v) => v.stringify(options),
+            TopLevel::Definition(v) => v.stringify(options),
+            TopLevel::Commented(c, v) => stringify_commented(c, v.as_ref(), options),
+        }
+    }
+    fn pretty_print<W>(
+        &self,
+        w: &mut W,
+        current_indentation: usize,
+        options: &Options,
+    ) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        match self {
+            TopLevel::Datum(v) => v.pretty_print(w, current_indentation, options),
+            TopLevel::Quoted(v) => v.pretty_print(w, current_indentation, options),
+            TopLevel::Definition(v) => v.pretty_print(w, current_indentation, options),
+            TopLevel::Commented(c, v) => {
+                pretty_print_commented(w, c, v.as_ref(), current_indentation, None, options)
+            }
+        }
+    }
+}
+
+impl<'a> TopLevel<'a> {
+    ///
+    /// Returns the datum to write at the top level, quoted if required by the options' quote
+    /// style and wrapped in a definition if required. [`QuoteStyle::All`] always quotes the datum,
+    /// [`QuoteStyle::AsNeeded`] only quotes values that would otherwise be evaluated. Definitions
+    /// are evaluated and so their values are quoted as needed even with [`QuoteStyle::None`].
+    /// Comments attached to the datum are written outside of any quote or definition.
+    ///
+    fn new(datum: &'a Value, options: &Options) -> Result<Self, Error> {
+        if let Value::Commented(commented) = datum {
+            return Ok(Self::Commented(
+                commented,
+                Box::new(Self::new(commented.value(), options)?),
+            ));
+        }
+        let quote = match options.quote() {
+            _ if *options.style() == LanguageStyle::TreeSitter => false,
+            QuoteStyle::None => *options.wrap_in_define() && is_evaluated(datum, options),
+            QuoteStyle::All(_) => true,
+            QuoteStyle::AsNeeded(_) => is_evaluated(datum, options),
+        };
+        if *options.style() == LanguageStyle::Racket {
+            check_racket_paths(datum, quote)?;
+        }
+        let top_level = if quote {
+            Self::Quoted(QuotedDatum::new(QuoteKind::Quote, datum))
+        } else {
+            Self::Datum(datum)
+        };
+        if *options.wrap_in_define() {
+            Ok(Self::Definition(Definition::new(top_level, options)?))
+        } else {
+            Ok(top_level)
+        }
+    }
+
+    fn has_comments(&self) -> bool {
+        match self {
+            TopLevel::Datum(v) => v.has_comments(),
+            TopLevel::Quoted(v) => v.datum.has_comments(),
+            TopLevel::Definition(v) => v.value.has_comments(),
+            TopLevel::Commented(_, _) => true,
+        }
+    }
+
+    fn ends_with_line_comment(&self, options: &Options) -> bool {
+        match self {
+            TopLevel::Datum(v) => ends_with_line_comment(v, options),
+            TopLevel::Quoted(v) => v.ends_with_line_comment(options),
+            TopLevel::Definition(_) => false,
+            TopLevel::Commented(c, v) => match c.trailing() {
+                Some(comment) => comment.is_line(),
+                None => v.ends_with_line_comment(options),
+            },
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Printable for Definition<'_> {
+    fn stringify(&self, options: &Options) -> Result<String, Error> {
+        let mut parts = vec![
+            self.operator.to_string(),
+            self.name.stringify(options)?,
+            self.value.stringify(options)?,
+        ];
+        if let Some(documentation) = &self.documentation {
+            parts.push(string_literal(documentation));
+        }
+        Ok(format!(
+            "{CHAR_LIST_OPEN}{}{CHAR_LIST_CLOSE}",
+            parts.join(" ")
+        ))
+    }
+    fn pretty_print<W>(
+        &self,
+        w: &mut W,
+        current_indentation: usize,
+        options: &Options,
+    ) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        if !self.value.has_comments()
+            && current_indentation + self.printed_length(options)? < *options.line_width()
+        {
+            self.print(w, options)
+        } else {
+            let body_indentation = current_indentation + DEFINITION_BODY_INDENT;
+            w.write_all(
+                format!(
+                    "{CHAR_LIST_OPEN}{} {}",
+                    self.operator,
+                    self.name.stringify(options)?
+                )
+                .as_bytes(),
+            )?;
+            newline_and_indent(body_indentation, w)?;
+            self.value.pretty_print(w, body_indentation, options)?;
+            if let Some(documentation) = &self.documentation {
+                newline_and_indent(body_indentation, w)?;
+                w.write_all(string_literal(documentation).as_bytes())?;
+            } else if self.value.ends_with_line_comment(options) {
+                newline_and_indent(body_indentation, w)?;
+            }
+            w.write_all(LIST_CLOSE.as_bytes())?;
+            Ok(())
+        }
+    }
+}
+
+impl<'a> Definition<'a> {
+    fn new(value: TopLevel<'a>, options: &Options) -> Result<Self, Error> {
+        let style = *options.style();
+        let kind = *options.definition_kind();
+        let name = options
+            .definition_name()
+            .ok_or(Error::MissingDefinitionName)?;
+        let operator = match (style, kind) {
+            (LanguageStyle::TreeSitter, _) => {
+                return Err(unsupported_in_style("definition", style))
+            }
+            (LanguageStyle::Racket | LanguageStyle::Scheme, _) => DEFINE,
+            (LanguageStyle::CommonLisp, DefinitionKind::Variable) => CLISP_DEFVAR,
+            (LanguageStyle::CommonLisp, DefinitionKind::Parameter) => CLISP_DEFPARAMETER,
+            (LanguageStyle::CommonLisp, DefinitionKind::Constant) => CLISP_DEFCONSTANT,
+            (LanguageStyle::EmacsLisp, DefinitionKind::Constant) => ELISP_DEFCONST,
+            (LanguageStyle::EmacsLisp, _) => ELISP_DEFVAR,
+        };
+        let name = if style == LanguageStyle::CommonLisp
+            && kind != DefinitionKind::Constant
+            && !(name.starts_with(CHAR_ASTERISK) && name.ends_with(CHAR_ASTERISK))
+        {
+            Symbol::new(format!("{CHAR_ASTERISK}{name}{CHAR_ASTERISK}"))
+        } else {
+            Symbol::new(name)
+        };
+        let documentation = match style {
+            LanguageStyle::CommonLisp | LanguageStyle::EmacsLisp => {
+                options.definition_documentation().cloned()
+            }
+            _ => None,
+        };
+        Ok(Self {
+            operator,
+            name,
+            value: Box::new(value),
+            documentation,
+        })
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Printable for DatumLabel {
+    fn stringify(&self, options: &Options) -> Result<String, Error> {
+        match self {
+            DatumLabel::Define(label, value) => Ok(format!(
+                "{}{}",
+                datum_label(*label, DATUM_LABEL_DEFINE, options)?,
+                value.stringify(options)?
+            )),
+            DatumLabel::Reference(label) => datum_label(*label, DATUM_LABEL_REFERENCE, options),
+        }
+    }
+    fn pretty_print<W>(
+        &self,
+        w: &mut W,
+        current_indentation: usize,
+        options: &Options,
+    ) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        match self {
+            DatumLabel::Define(label, value) => {
+                let prefix = datum_label(*label, DATUM_LABEL_DEFINE, options)?;
+                w.write_all(prefix.as_bytes())?;
+                value.pretty_print(w, current_indentation + prefix.len(), options)
+            }
+            DatumLabel::Reference(_) => self.print(w, options),
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl DatumLabeler {
+    ///
+    /// Count the references to each shared value, the values within a shared value are only
+    /// counted the first time it is seen. All other containers are walked through
+    /// [`Value::children`].
+    ///
+    fn count(&mut self, value: &Value) {
+        if let Value::Shared(shared) = value {
+            let count = self.counts.entry(shared.as_ptr()).or_default();
+            *count += 1;
+            if *count == 1 {
+                if let Some(value) = shared.get() {
+                    self.count(value)
+                }
+            }
+        } else {
+            value
+                .children()
+                .into_iter()
+                .for_each(|value| self.count(value));
+        }
+    }
+
+    ///
+    /// Returns a copy of `value` with shared values replaced by their values, if referenced
+    /// once, or by datum labels.
+    ///
+    fn label(&mut self, value: &Value) -> Result<Value, Error> {
+        Ok(match value {
+            Value::Shared(shared) => self.label_shared(shared)?,
+            Value::List(values) => Value::List(self.label_all(values)?),
+            Value::Vector(values) => Value::Vector(self.label_all(values)?),
+            Value::DottedList(list) => {
+                Value::improper_list(self.label_all(list.items())?, self.label(list.tail())?)
+            }
+            Value::Map(map) => {
+                let mut new_map = Map::new().with_test(*map.test());
+                for (k, v) in map.entries() {
+                    new_map.insert(self.label(k)?, self.label(v)?);
+                }
+                new_map.into()
+            }
+            _ => {
+                // any other container is rebuilt around its labeled children.
+                let children = self.label_all(value.children())?;
+                let mut new_value = value.clone();
+                if !children.is_empty() {
+                    new_value.take_children();
+                    new_value.restore_children(children);
+                }
+                new_value
+            }
+        })
+    }
+
+    fn label_all<'a, I>(&mut self, values: I) -> Result<Vec<Value>, Error>
+    where
+        I: IntoIterator<Item = &'a Value>,
+    {
+        values.into_iter().map(|value| self.label(value)).collect()
+    }
+
+    fn label_shared(&mut self, shared: &Shared) -> Result<Value, Error> {
+        let ptr = shared.as_ptr();
+        if let Some(label) = self.labels.get(&ptr) {
+            return Ok(DatumLabel::reference(*label).into());
+        }
+        let value = shared.get().ok_or(Error::UnsetSharedValue)?;
+        if self.counts.get(&ptr).copied().unwrap_or_default() > 1 {
+            let label = self.labels.len();
+            self.labels.insert(ptr, label);
+            Ok(DatumLabel::define(label, self.label(value)?).into())
+        } else {
+            self.label(value)
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Printable for SequenceItem<'_> {
+    fn stringify(&self, options: &Options) -> Result<String, Error> {
+        match self {
+            SequenceItem::Value(v) => v.stringify(options),
+            SequenceItem::Tail(v) => Ok(format!("{CHAR_PERIOD} {}", v.stringify(options)?)),
+        }
+    }
+    fn pretty_print<W>(
+        &self,
+        w: &mut W,
+        current_indentation: usize,
+        options: &Options,
+    ) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        match self {
+            SequenceItem::Value(v) => v.pretty_print(w, current_indentation, options),
+            SequenceItem::Tail(v) => {
+                w.write_all(DOT_SPACE)?;
+                v.pretty_print(w, current_indentation + DOT_SPACE.len(), options)
+            }
+        }
+    }
+}
+
+impl<'a> SequenceItem<'a> {
+    fn value(&self) -> &'a Value {
+        match self {
+            SequenceItem::Value(v) | SequenceItem::Tail(v) => v,
+        }
+    }
+
+    fn list(values: &'a [Value]) -> Vec<Self> {
+        values.iter().map(SequenceItem::Value).collect()
+    }
+
+    fn dotted_list(list: &'a DottedList, options: &Options) -> Result<Vec<Self>, Error> {
+        let style = *options.style();
+        let mut items: Vec<Self> = Self::list(list.items());
+        let mut tail = list.tail();
+        loop {
+            match tail {
+                Value::DottedList(list) => {
+                    items.extend(Self::list(list.items()));
+                    tail = list.tail();
+                }
+                Value::List(values) => {
+                    items.extend(Self::list(values));
+                    break;
+                }
+                _ => {
+                    if style == LanguageStyle::TreeSitter {
+                        return Err(unsupported_in_style("dotted list", style));
+                    }
+                    items.push(SequenceItem::Tail(tail));
+                    break;
+                }
+            }
+        }
+        Ok(items)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Printable for ItemGroup<'_, '_> {
+    fn stringify(&self, options: &Options) -> Result<String, Error> {
+        match self.items {
+            [key, value] => {
+                let key = key.stringify(options)?;
+                Ok(format!(
+                    "{key:key_width$} {}",
+                    value.stringify(options)?,
+                    key_width = self.key_width
+                ))
+            }
+            _ => stringify_sequence(STR_EMPTY, self.items, STR_EMPTY, options),
+        }
+    }
+    fn pretty_print<W>(
+        &self,
+        w: &mut W,
+        current_indentation: usize,
+        options: &Options,
+    ) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        match self.items {
+            [key, value] => {
+                let key = key.stringify(options)?;
+                let key_width = self.key_width.max(key.len());
+                w.write_all(format!("{key:key_width$} ").as_bytes())?;
+                value.pretty_print(w, current_indentation + key_width + 1, options)
+            }
+            _ => {
+                for item in self.items {
+                    item.pretty_print(w, current_indentation, options)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl<'a, 'b> ItemGroup<'a, 'b> {
+    fn group(items: &'b [SequenceItem<'a>], options: &Options) -> Result<Vec<Self>, Error> {
+        let mut groups: Vec<Self> = Vec::with_capacity(items.len());
+        let mut i = 0;
+        while i < items.len() {
+            let is_pair = *options.pair_kw_args()
+                && matches!(items[i], SequenceItem::Value(Value::Keyword(_)))
+                && matches!(items.get(i + 1), Some(SequenceItem::Value(_)));
+            let length = if is_pair { 2 } else { 1 };
+            groups.push(Self {
+                items: &items[i..i + length],
+                key_width: 0,
+            });
+            i += length;
+        }
+        if *options.align_kw_args() {
+            let key_width = groups
+                .iter()
+                .filter(|group| group.is_pair())
+                .map(|group| group.items[0].printed_length(options))
+                .collect::<Result<Vec<_>, _>>()?
+                .into_iter()
+                .max()
+                .unwrap_or_default();
+            groups
+                .iter_mut()
+                .filter(|group| group.is_pair())
+                .for_each(|group| group.key_width = key_width);
+        }
+        Ok(groups)
+    }
+
+    fn is_pair(&self) -> bool {
+        self.items.len() == 2
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Printable for Value {
+    fn stringify(&self, options: &Options) -> Result<String, Error> {
+        match self {
+            Value::Bool(v) => v.stringify(options),
+            Value::Number(v) => v.stringify(options),
+            Value::Character(v) => v.stringify(options),
+            Value::String(v) => v.stringify(options),
+            Value::Symbol(v) => v.stringify(options),
+            Value::Keyword(v) => v.stringify(options),
+            Value::List(v) => v.stringify(options),
+            Value::DottedList(v) => v.stringify(options),
+            Value::Map(v) => v.stringify(options),
+            Value::Bytes(v) => v.stringify(options),
+            Value::Quoted(v) => v.stringify(options),
+            Value::Commented(v) => v.stringify(options),
+            Value::Struct(v) => v.stringify(options),
+            Value::Regexp(v) => v.stringify(options),
+            Value::Path(v) => v.stringify(options),
+            Value::BitVector(v) => v.stringify(options),
+            Value::Array(v) => v.stringify(options),
+            Value::ModifiedChar(v) => v.stringify(options),
+            Value::PropertizedString(v) => v.stringify(options),
+            Value::Node(v) => v.stringify(options),
+            Value::DatumLabel(v) => v.stringify(options),
+            Value::Shared(v) => v.get().ok_or(Error::UnsetSharedValue)?.stringify(options),
+            Value::Boxed(v) => Ok(format!("{}{}", box_prefix(options)?, v.stringify(options)?)),
+            Value::Vector(v) => {
+                let (open, close) = vector_delimiters(*options.style())?;
+                stringify_sequence(&open, &SequenceItem::list(v), &close, options)
+            }
+        }
+    }
+    fn pretty_print<W>(
+        &self,
+        w: &mut W,
+        current_indentation: usize,
+        options: &Options,
+    ) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        match self {
+            Value::Bool(v) => v.pretty_print(w, current_indentation, options),
+            Value::Number(v) => v.pretty_print(w, current_indentation, options),
+            Value::Character(v) => v.pretty_print(w, current_indentation, options),
+            Value::String(v) => v.pretty_print(w, current_indentation, options),
+            Value::Symbol(v) => v.pretty_print(w, current_indentation, options),
+            Value::Keyword(v) => v.pretty_print(w, current_indentation, options),
+            Value::List(v) => v.pretty_print(w, current_indentation, options),
+            Value::DottedList(v) => v.pretty_print(w, current_indentation, options),
+            Value::Map(v) => v.pretty_print(w, current_indentation, options),
+            Value::Bytes(v) => v.pretty_print(w, current_indentation, options),
+            Value::Quoted(v) => v.pretty_print(w, current_indentation, options),
+            Value::Commented(v) => v.pretty_print(w, current_indentation, options),
+            Value::Struct(v) => v.pretty_print(w, current_indentation, options),
+            Value::Regexp(v) => v.pretty_print(w, current_indentation, options),
+            Value::Path(v) => v.pretty_print(w, current_indentation, options),
+            Value::BitVector(v) => v.pretty_print(w, current_indentation, options),
+            Value::Array(v) => v.pretty_print(w, current_indentation, options),
+            Value::ModifiedChar(v) => v.pretty_print(w, current_indentation, options),
+            Value::PropertizedString(v) => v.pretty_print(w, current_indentation, options),
+            Value::Node(v) => v.pretty_print(w, current_indentation, options),
+            Value::DatumLabel(v) => v.pretty_print(w, current_indentation, options),
+            Value::Shared(v) => v.get().ok_or(Error::UnsetSharedValue)?.pretty_print(
+                w,
+                current_indentation,
+                options,
+            ),
+            Value::Boxed(v) => {
+                let prefix = box_prefix(options)?;
+                w.write_all(prefix.as_bytes())?;
+                v.pretty_print(w, current_indentation + prefix.len(), options)
+            }
+            Value::Vector(v) => {
+                let (open, close) = vector_delimiters(*options.style())?;
+                pretty_print_sequence(
+                    w,
+                    &open,
+                    &SequenceItem::list(v),
+                    &close,
+                    current_indentation,
+                    options,
+                )
+            }
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Write one part of a complex number, keeping the decimal point of integral values so that
+/// the part is read back as inexact.
+///
+fn complex_part(value: f64, style: LanguageStyle) -> Result<String, Error> {
+    match style {
+        _ if value.is_finite() => Ok(format!("{value:?}")),
+        LanguageStyle::Racket | LanguageStyle::Scheme => Ok(if value.is_nan() {
+            STR_NAN
+        } else if value.is_sign_positive() {
+            STR_POSITIVE_INFINITY
+        } else {
+            STR_NEGATIVE_INFINITY
+        }
+        .to_string()),
+        _ => Err(unsupported_in_style(
+            format!("complex with a non-finite part {value}"),
+            style,
+        )),
+    }
+}
+
+#[inline(always)]
+fn is_char_nonprintable(c: char) -> bool {
+    matches!(c,
         '\u{0000}'..'\u{0008}'
         | '\u{000B}'..'\u{001F}'
         | '\u{007F}'..'\u{009F}'
@@ -734,12 +2170,713 @@ fn is_char_nonprintable(c: char) -> bool {
         | '\u{E0100}'..'\u{E01EF}')
 }
 
+///
+/// Write `s` as a string literal escaping only `"` and `\`, for the pattern of a regexp or a
+/// path, where the escapes added to symbol-like characters by the string printer would change
+/// the value read back, and for a documentation string, as Common Lisp has no other escapes and
+/// reads `\n` as `n`.
+///
+fn string_literal(s: &str) -> String {
+    let mut result = String::with_capacity(s.len() + 2);
+    result.push(CHAR_DOUBLE_QUOTE);
+    for c in s.chars() {
+        if c == CHAR_DOUBLE_QUOTE || c == CHAR_BACKSLASH {
+            result.push(CHAR_BACKSLASH);
+        }
+        result.push(c);
+    }
+    result.push(CHAR_DOUBLE_QUOTE);
+    result
+}
+
+fn sequence_printed_length(items: &[SequenceItem<'_>], options: &Options) -> Result<usize, Error> {
+    let mut length = 0;
+    for item in items {
+        length += item.printed_length(options)?;
+    }
+    // add inter-datum spaces
+    Ok(length + if items.len() < 2 { 0 } else { items.len() - 1 })
+}
+
+///
+/// Vectors are written as `#(...)` in Racket, Scheme, and Common Lisp but as `[...]` in Emacs
+/// Lisp; tree-sitter has no vector syntax.
+///
+fn vector_delimiters(style: LanguageStyle) -> Result<(String, String), Error> {
+    match style {
+        LanguageStyle::TreeSitter => Err(unsupported_in_style("vector", style)),
+        LanguageStyle::EmacsLisp => {
+            Ok((CHAR_SQLIST_OPEN.to_string(), CHAR_SQLIST_CLOSE.to_string()))
+        }
+        _ => Ok((
+            format!("{CHAR_NUMBER_SIGN}{CHAR_LIST_OPEN}"),
+            CHAR_LIST_CLOSE.to_string(),
+        )),
+    }
+}
+
+///
+/// Racket paths are written as `(string->path "...")`, which is only a path when evaluated, so
+/// it is an error for a path to appear in quoted data, or in a literal such as a vector or hash
+/// which is read as data. An unquote returns to evaluated code.
+///
+fn check_racket_paths(value: &Value, in_data: bool) -> Result<(), Error> {
+    match value {
+        Value::Path(_) if in_data => Err(unsupported_in_style(
+            "path in quoted data",
+            LanguageStyle::Racket,
+        )),
+        Value::Quoted(quoted) => check_racket_paths(
+            quoted.datum(),
+            !matches!(
+                quoted.kind(),
+                QuoteKind::Unquote | QuoteKind::UnquoteSplicing
+            ),
+        ),
+        Value::List(_) | Value::DottedList(_) | Value::Commented(_) | Value::DatumLabel(_) => value
+            .children()
+            .into_iter()
+            .try_for_each(|child| check_racket_paths(child, in_data)),
+        _ => value
+            .children()
+            .into_iter()
+            .try_for_each(|child| check_racket_paths(child, true)),
+    }
+}
+
+///
+/// Returns `true` if `value` would be evaluated, rather than being self-evaluating, when read as
+/// an expression in the options' style. Note that the empty list, and keywords, are not valid
+/// expressions in Racket or Scheme.
+///
+fn is_evaluated(value: &Value, options: &Options) -> bool {
+    let style = *options.style();
+    // in these the empty list is `nil` and keywords evaluate to themselves.
+    let has_nil = matches!(style, LanguageStyle::CommonLisp | LanguageStyle::EmacsLisp);
+    match value {
+        Value::Symbol(_) | Value::DottedList(_) => true,
+        Value::DatumLabel(DatumLabel::Define(_, value)) => is_evaluated(value, options),
+        Value::Keyword(_) => !has_nil,
+        Value::List(values) => !(has_nil && values.is_empty()),
+        Value::Map(map) => {
+            matches!(style, LanguageStyle::CommonLisp | LanguageStyle::Scheme) && !map.is_empty()
+        }
+        _ => false,
+    }
+}
+
+fn quote_prefix(kind: QuoteKind, options: &Options) -> Result<QuotePrefix, Error> {
+    let style = *options.style();
+    if style == LanguageStyle::TreeSitter {
+        return Err(unsupported_in_style("quoted datum", style));
+    }
+    if kind == QuoteKind::Function
+        && !matches!(style, LanguageStyle::CommonLisp | LanguageStyle::EmacsLisp)
+    {
+        return Err(unsupported_in_style("function reference", style));
+    }
+    let long_form = options.quote().is_long_form()
+        && (matches!(kind, QuoteKind::Quote | QuoteKind::Function)
+            || style != LanguageStyle::CommonLisp);
+    Ok(match (kind, long_form, style) {
+        (QuoteKind::Quote, false, _) => QuotePrefix::Short(STR_QUOTE),
+        (QuoteKind::QuasiQuote, false, _) => QuotePrefix::Short(STR_QUASI_QUOTE),
+        (QuoteKind::Unquote, false, _) => QuotePrefix::Short(STR_UNQUOTE),
+        (QuoteKind::UnquoteSplicing, false, _) => QuotePrefix::Short(STR_UNQUOTE_SPLICING),
+        (QuoteKind::Function, false, _) => QuotePrefix::Short(STR_FUNCTION),
+        (QuoteKind::Function, true, _) => QuotePrefix::Long(FUNCTION_LONG),
+        (QuoteKind::Quote, true, _) => QuotePrefix::Long(QUOTE_LONG),
+        (QuoteKind::QuasiQuote, true, LanguageStyle::EmacsLisp) => {
+            QuotePrefix::Long(ELISP_QUASI_QUOTE_LONG)
+        }
+        (QuoteKind::Unquote, true, LanguageStyle::EmacsLisp) => {
+            QuotePrefix::Long(ELISP_UNQUOTE_LONG)
+        }
+        (QuoteKind::UnquoteSplicing, true, LanguageStyle::EmacsLisp) => {
+            QuotePrefix::Long(ELISP_UNQUOTE_SPLICING_LONG)
+        }
+        (QuoteKind::QuasiQuote, true, _) => QuotePrefix::Long(QUASI_QUOTE_LONG),
+        (QuoteKind::Unquote, true, _) => QuotePrefix::Long(UNQUOTE_LONG),
+        (QuoteKind::UnquoteSplicing, true, _) => QuotePrefix::Long(UNQUOTE_SPLICING_LONG),
+    })
+}
+
+fn array_prefix(array: &Array, options: &Options) -> Result<String, Error> {
+    match options.style() {
+        LanguageStyle::CommonLisp => Ok(format!(
+            "{CHAR_NUMBER_SIGN}{}{CLISP_ARRAY_SUFFIX}",
+            array.rank()
+        )),
+        style => Err(unsupported_in_style("array", *style)),
+    }
+}
+
+///
+/// Returns the row-major `elements` nested as lists, one level for each of the `dimensions`;
+/// a zero-rank array is its single element.
+///
+fn nested_array_elements(dimensions: &[usize], elements: &[Value]) -> Value {
+    match dimensions.split_first() {
+        None => elements[0].clone(),
+        Some((dimension, rest)) => {
+            let stride = rest.iter().product::<usize>();
+            if stride == 0 {
+                (0..*dimension)
+                    .map(|_| nested_array_elements(rest, elements))
+                    .collect()
+            } else {
+                elements
+                    .chunks(stride)
+                    .map(|chunk| nested_array_elements(rest, chunk))
+                    .collect()
+            }
+        }
+    }
+}
+
+fn propertized_string_open(options: &Options) -> Result<&'static str, Error> {
+    match options.style() {
+        LanguageStyle::EmacsLisp => Ok(ELISP_PROPERTIZED_OPEN),
+        style => Err(unsupported_in_style("propertized string", *style)),
+    }
+}
+
+fn propertized_string_values(value: &PropertizedString) -> Vec<Value> {
+    std::iter::once(Value::from(value.text()))
+        .chain(value.properties().iter().flat_map(|properties| {
+            [
+                Value::from(properties.start() as i64),
+                Value::from(properties.end() as i64),
+                properties.to_property_list(),
+            ]
+        }))
+        .collect()
+}
+
+///
+/// Returns the written form of a node up to, but not including, its children.
+///
+fn node_head(node: &Node, options: &Options) -> Result<String, Error> {
+    let style = *options.style();
+    if style != LanguageStyle::TreeSitter {
+        return Err(unsupported_in_style("node", style));
+    }
+    let field = node
+        .field()
+        .map(|field| format!("{field}{TREE_SITTER_FIELD_SEPARATOR}"))
+        .unwrap_or_default();
+    let range = node
+        .range()
+        .map(|(start, end)| {
+            format!(
+                " [{}, {}] - [{}, {}]",
+                start.row(),
+                start.column(),
+                end.row(),
+                end.column()
+            )
+        })
+        .unwrap_or_default();
+    Ok(match node.kind() {
+        NodeKind::Named(kind) => format!("{field}{CHAR_LIST_OPEN}{kind}{range}"),
+        NodeKind::Anonymous(text) => format!("{field}{}{range}", quote_node_text(text)),
+        NodeKind::Missing { kind, named } => format!(
+            "{field}{CHAR_LIST_OPEN}{TREE_SITTER_MISSING} {}{range}",
+            if *named {
+                kind.clone()
+            } else {
+                quote_node_text(kind)
+            }
+        ),
+        NodeKind::Error => format!("{field}{CHAR_LIST_OPEN}{TREE_SITTER_ERROR}{range}"),
+    })
+}
+
+///
+/// Returns the closing delimiter of a node, or `None` for anonymous nodes which are written
+/// without delimiters or children.
+///
+fn node_close(node: &Node) -> Option<&'static str> {
+    match node.kind() {
+        NodeKind::Anonymous(_) => None,
+        _ => Some(LIST_CLOSE),
+    }
+}
+
+fn quote_node_text(text: &str) -> String {
+    format!(
+        "{CHAR_DOUBLE_QUOTE}{}{CHAR_DOUBLE_QUOTE}",
+        text.chars()
+            .map(|c| match c {
+                CHAR_DOUBLE_QUOTE | CHAR_BACKSLASH => format!("{CHAR_BACKSLASH}{c}"),
+                '\n' => "\\n".to_string(),
+                '\r' => "\\r".to_string(),
+                '\t' => "\\t".to_string(),
+                '\0' => "\\0".to_string(),
+                _ => c.to_string(),
+            })
+            .collect::<String>()
+    )
+}
+
+///
+/// Escape one part of a symbol, its name or qualifier, wrapping it in `|...|` whenever
+/// [`symbol_part_escape_reason`] finds that it could not otherwise be read back as the same
+/// symbol; within the bars `|` and `\` are escaped with a backslash.
+///
+fn escape_symbol_part(s: &str, style: LanguageStyle) -> String {
+    if style == LanguageStyle::TreeSitter {
+        // a shame...
+        return s.to_string();
+    }
+    if symbol_part_escape_reason(s, style).is_none() {
+        return s.to_string();
+    }
+    if let Some(rest) = s
+        .strip_prefix(RACKET_SYMBOL_PREFIX)
+        .filter(|rest| style == LanguageStyle::Racket && !rest.is_empty())
+    {
+        // `#%` starts a symbol in Racket, so only the rest of the name needs escaping.
+        return format!("{RACKET_SYMBOL_PREFIX}{}", escape_symbol_part(rest, style));
+    }
+    let new_s = s
+        .chars()
+        .map(|c| {
+            if c == CHAR_VERTICAL_BAR || c == CHAR_BACKSLASH {
+                format!("{CHAR_BACKSLASH}{c}")
+            } else if is_char_nonprintable(c) && style == LanguageStyle::Racket {
+                // other styles read any character other than `|` and `\` verbatim between bars.
+                string_escape_char(c, style)
+            } else {
+                c.to_string()
+            }
+        })
+        .collect::<String>();
+    format!("{CHAR_VERTICAL_BAR}{new_s}{CHAR_VERTICAL_BAR}")
+}
+
+///
+/// Datum labels, `#0=` and `#0#`, are supported by all styles other than tree-sitter.
+///
+fn datum_label(label: usize, suffix: char, options: &Options) -> Result<String, Error> {
+    match options.style() {
+        LanguageStyle::TreeSitter => Err(unsupported_in_style("datum label", *options.style())),
+        _ => Ok(format!("{CHAR_NUMBER_SIGN}{label}{suffix}")),
+    }
+}
+
+fn box_prefix(options: &Options) -> Result<&'static str, Error> {
+    match options.style() {
+        LanguageStyle::Racket => Ok(RACKET_BOX_PREFIX),
+        style => Err(unsupported_in_style("box", *style)),
+    }
+}
+
+fn struct_open(options: &Options) -> Result<&'static str, Error> {
+    match options.style() {
+        LanguageStyle::Racket => Ok(RACKET_PREFAB_OPEN),
+        LanguageStyle::EmacsLisp => Ok(ELISP_RECORD_OPEN),
+        style => Err(unsupported_in_style("struct", *style)),
+    }
+}
+
+fn struct_values(value: &Struct) -> Vec<Value> {
+    std::iter::once(Value::from(value.name()))
+        .chain(value.fields().iter().cloned())
+        .collect()
+}
+
+fn byte_values(bytes: &[u8]) -> Vec<Value> {
+    bytes.iter().map(Value::from).collect()
+}
+
+///
+/// Escape a byte string; printable ASCII is written as-is, with the exception of the string
+/// delimiter and backslash, all other bytes use three-digit octal escapes which are read the
+/// same way by Racket and Emacs Lisp.
+///
+fn escape_bytes(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|b| match *b {
+            b'"' | b'\\' => format!("{CHAR_BACKSLASH}{}", *b as char),
+            0x20..=0x7E => (*b as char).to_string(),
+            _ => format!("{CHAR_BACKSLASH}{:03o}", b),
+        })
+        .collect()
+}
+
+///
+/// Returns the opening delimiter and the values within the written form of `map`.
+///
+fn map_sequence(map: &Map, options: &Options) -> Result<(&'static str, Vec<Value>), Error> {
+    let entries = || -> Vec<Value> {
+        map.entries()
+            .iter()
+            .map(|(k, v)| Value::pair(k.clone(), v.clone()))
+            .collect()
+    };
+    let data = || -> Vec<Value> {
+        map.entries()
+            .iter()
+            .flat_map(|(k, v)| [k.clone(), v.clone()])
+            .collect()
+    };
+    match (options.style(), options.map_fallback()) {
+        (LanguageStyle::Racket, _) => Ok((
+            match map.test() {
+                MapTest::Equal => RACKET_HASH_EQUAL_OPEN,
+                MapTest::Eqv => RACKET_HASH_EQV_OPEN,
+                MapTest::Eq => RACKET_HASH_EQ_OPEN,
+            },
+            entries(),
+        )),
+        (LanguageStyle::EmacsLisp, _) => Ok((
+            ELISP_RECORD_OPEN,
+            vec![
+                Symbol::new(ELISP_HASH_TABLE).into(),
+                Symbol::new(ELISP_HASH_TEST).into(),
+                Symbol::new(match map.test() {
+                    MapTest::Equal => ELISP_TEST_EQUAL,
+                    MapTest::Eqv => ELISP_TEST_EQL,
+                    MapTest::Eq => ELISP_TEST_EQ,
+                })
+                .into(),
+                Symbol::new(ELISP_HASH_DATA).into(),
+                Value::List(data()),
+            ],
+        )),
+        (LanguageStyle::CommonLisp | LanguageStyle::Scheme, MapFallback::AssociationList) => {
+            Ok((LIST_OPEN, entries()))
+        }
+        (LanguageStyle::CommonLisp | LanguageStyle::Scheme, MapFallback::PropertyList) => {
+            Ok((LIST_OPEN, data()))
+        }
+        (LanguageStyle::TreeSitter, _) => Err(unsupported_in_style("map", *options.style())),
+    }
+}
+
+fn stringify_sequence(
+    open: &str,
+    items: &[SequenceItem<'_>],
+    close: &str,
+    options: &Options,
+) -> Result<String, Error> {
+    Ok(format!(
+        "{}{}{}",
+        open,
+        items
+            .iter()
+            .map(|v| v.stringify(options))
+            .collect::<Result<Vec<String>, Error>>()?
+            .join(" "),
+        close,
+    ))
+}
+
+fn print_sequence<W>(
+    w: &mut W,
+    open: &str,
+    items: &[SequenceItem<'_>],
+    close: &str,
+    options: &Options,
+) -> Result<(), Error>
+where
+    W: Write,
+{
+    w.write_all(open.as_bytes())?;
+    for (value, is_last) in items
+        .iter()
+        .enumerate()
+        .map(|(i, v)| (v, i == items.len() - 1))
+    {
+        value.print(w, options)?;
+        if !is_last {
+            w.write_all(SPACE)?;
+        }
+    }
+    w.write_all(close.as_bytes())?;
+    Ok(())
+}
+
+fn pretty_print_sequence<W>(
+    w: &mut W,
+    open: &str,
+    items: &[SequenceItem<'_>],
+    close: &str,
+    current_indentation: usize,
+    options: &Options,
+) -> Result<(), Error>
+where
+    W: Write,
+{
+    if items.iter().any(|item| item.value().has_comments()) {
+        return pretty_print_commented_sequence(
+            w,
+            open,
+            items,
+            close,
+            current_indentation,
+            options,
+        );
+    }
+    let line_width = *options.line_width();
+    let print_width = sequence_printed_length(items, options)?;
+    if items.is_empty() || current_indentation + print_width < line_width {
+        print_sequence(w, open, items, close, options)?;
+    } else {
+        let groups = ItemGroup::group(items, options)?;
+        let align = *options.pair_kw_args() && *options.align_kw_args();
+        let current_indentation = current_indentation + open.len();
+        let mut current_width = current_indentation;
+        let mut column = current_indentation;
+        let mut pair_column: Option<usize> = None;
+        w.write_all(open.as_bytes())?;
+        let last_value_index: usize = groups.len() - 1;
+        for (i, v) in groups.iter().enumerate() {
+            let width = v.printed_length(options)?;
+            current_width += width;
+            column += width;
+            v.pretty_print(w, pair_column.unwrap_or(current_indentation), options)?;
+            if i < last_value_index {
+                let next = groups.get(i + 1).unwrap();
+                let next_width = next.printed_length(options)?;
+                if align && (pair_column.is_some() || next.is_pair()) {
+                    // a hanging indent, with each pair on its own line.
+                    match pair_column {
+                        None if column + next_width + 1 < line_width => {
+                            w.write_all(SPACE)?;
+                            pair_column = Some(column + 1);
+                        }
+                        _ => {
+                            let indentation = *pair_column.get_or_insert(current_indentation);
+                            newline_and_indent(indentation, w)?;
+                        }
+                    }
+                    column = pair_column.unwrap();
+                    current_width = column;
+                } else if (current_width + next_width + 1) >= line_width {
+                    newline_and_indent(current_indentation, w)?;
+                    current_width = current_indentation;
+                    column = current_indentation;
+                } else {
+                    w.write_all(SPACE)?;
+                    column += 1;
+                }
+            }
+        }
+        w.write_all(close.as_bytes())?;
+    }
+    Ok(())
+}
+
+///
+/// Sequences containing comments are written with one item per line, so that leading comments
+/// are on their own lines, and with trailing comments aligned in a single column.
+///
+fn pretty_print_commented_sequence<W>(
+    w: &mut W,
+    open: &str,
+    items: &[SequenceItem<'_>],
+    close: &str,
+    current_indentation: usize,
+    options: &Options,
+) -> Result<(), Error>
+where
+    W: Write,
+{
+    let current_indentation = current_indentation + open.len();
+    let comment_column = items
+        .iter()
+        .filter_map(|item| match item {
+            SequenceItem::Value(Value::Commented(c)) if c.trailing().is_some() => {
+                Some(c.value().printed_length(options))
+            }
+            SequenceItem::Tail(Value::Commented(c)) if c.trailing().is_some() => Some(
+                c.value()
+                    .printed_length(options)
+                    .map(|width| DOT_SPACE.len() + width),
+            ),
+            _ => None,
+        })
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .max()
+        .map(|width| current_indentation + width + 1);
+    w.write_all(open.as_bytes())?;
+    for (position, item) in items.iter().with_position() {
+        match item {
+            SequenceItem::Value(Value::Commented(c)) => pretty_print_commented(
+                w,
+                c,
+                c.value(),
+                current_indentation,
+                comment_column,
+                options,
+            )?,
+            SequenceItem::Tail(Value::Commented(c)) => {
+                w.write_all(DOT_SPACE)?;
+                pretty_print_commented(
+                    w,
+                    c,
+                    c.value(),
+                    current_indentation + DOT_SPACE.len(),
+                    comment_column,
+                    options,
+                )?
+            }
+            _ => item.pretty_print(w, current_indentation, options)?,
+        }
+        if !matches!(position, Position::Last | Position::Only)
+            || ends_with_line_comment(item.value(), options)
+        {
+            newline_and_indent(current_indentation, w)?;
+        }
+    }
+    w.write_all(close.as_bytes())?;
+    Ok(())
+}
+
+///
+/// Returns the written lines of a comment, or an error if the options' style does not support
+/// the kind of comment.
+///
+fn comment_lines(comment: &Comment, options: &Options) -> Result<Vec<String>, Error> {
+    let style = *options.style();
+    match (comment, style) {
+        (_, LanguageStyle::TreeSitter) => Err(unsupported_in_style("comment", style)),
+        (Comment::Line(level, text), _) => {
+            let prefix = match level {
+                LineCommentLevel::Margin => LINE_COMMENT_MARGIN,
+                LineCommentLevel::Code => LINE_COMMENT_CODE,
+                LineCommentLevel::Section => LINE_COMMENT_SECTION,
+            };
+            Ok(text
+                .split(CHAR_NEWLINE)
+                .map(|line| {
+                    if line.is_empty() {
+                        prefix.to_string()
+                    } else {
+                        format!("{prefix} {line}")
+                    }
+                })
+                .collect())
+        }
+        (Comment::Block(_), LanguageStyle::EmacsLisp) => {
+            Err(unsupported_in_style("block comment", style))
+        }
+        // block comments nest, so either delimiter in the text would change where it ends.
+        (Comment::Block(text), _)
+            if text.contains(BLOCK_COMMENT_OPEN) || text.contains(BLOCK_COMMENT_CLOSE) =>
+        {
+            Err(unsupported_in_style(
+                format!("block comment containing {BLOCK_COMMENT_OPEN} or {BLOCK_COMMENT_CLOSE}"),
+                style,
+            ))
+        }
+        (Comment::Block(text), _) => Ok(vec![format!(
+            "{BLOCK_COMMENT_OPEN} {text} {BLOCK_COMMENT_CLOSE}"
+        )]),
+        (Comment::Datum(_), LanguageStyle::CommonLisp | LanguageStyle::EmacsLisp) => {
+            Err(unsupported_in_style("datum comment", style))
+        }
+        (Comment::Datum(datum), _) => Ok(vec![format!(
+            "{DATUM_COMMENT}{}",
+            datum.stringify(options)?
+        )]),
+    }
+}
+
+///
+/// Returns `true` if the written form of `value` ends with a line comment, in which case anything
+/// following it must start on a new line.
+///
+fn ends_with_line_comment(value: &Value, options: &Options) -> bool {
+    match value {
+        Value::Commented(c) => c.ends_with_line_comment(),
+        Value::Quoted(q) => QuotedDatum::new(q.kind(), q.datum()).ends_with_line_comment(options),
+        _ => false,
+    }
+}
+
+///
+/// Write `value` with the comments from `commented`; a trailing line comment is followed by a
+/// newline so that the written form may be followed by other values.
+///
+fn stringify_commented<P>(
+    commented: &Commented,
+    value: &P,
+    options: &Options,
+) -> Result<String, Error>
+where
+    P: Printable,
+{
+    let mut result = String::new();
+    for comment in commented.leading() {
+        result.push_str(&comment.stringify(options)?);
+        result.push(if comment.is_line() {
+            CHAR_NEWLINE
+        } else {
+            CHAR_SPACE
+        });
+    }
+    result.push_str(&value.stringify(options)?);
+    if let Some(comment) = commented.trailing() {
+        result.push(CHAR_SPACE);
+        result.push_str(&comment.stringify(options)?);
+        if comment.is_line() {
+            result.push(CHAR_NEWLINE);
+        }
+    }
+    Ok(result)
+}
+
+///
+/// Pretty print `value` with the comments from `commented`; each leading comment is written on
+/// its own line and the trailing comment, if any, is written at `comment_column` or after a
+/// single space if the value extends past it. The trailing comment is not followed by a newline.
+///
+fn pretty_print_commented<W, P>(
+    w: &mut W,
+    commented: &Commented,
+    value: &P,
+    current_indentation: usize,
+    comment_column: Option<usize>,
+    options: &Options,
+) -> Result<(), Error>
+where
+    W: Write,
+    P: Printable,
+{
+    for comment in commented.leading() {
+        comment.pretty_print(w, current_indentation, options)?;
+        newline_and_indent(current_indentation, w)?;
+    }
+    let mut buffer: Vec<u8> = Vec::default();
+    value.pretty_print(&mut buffer, current_indentation, options)?;
+    w.write_all(&buffer)?;
+    if let Some(comment) = commented.trailing() {
+        let written = String::from_utf8_lossy(&buffer);
+        let column = match written.rfind(CHAR_NEWLINE) {
+            Some(index) => written[index + 1..].chars().count(),
+            None => current_indentation + written.chars().count(),
+        };
+        let padding = comment_column
+            .unwrap_or_default()
+            .saturating_sub(column)
+            .max(1);
+        w.write_all(format!("{:padding$}", STR_EMPTY).as_bytes())?;
+        comment.pretty_print(w, column + padding, options)?;
+    }
+    Ok(())
+}
+
 #[inline(always)]
 fn newline_and_indent<W>(indent: usize, writer: &mut W) -> Result<(), Error>
 where
     W: Write,
 {
-    writer.write_all(format!("{CHAR_NEWLINE}{:indent$}", " ").as_bytes())?;
+    writer.write_all(format!("{CHAR_NEWLINE}{:indent$}", STR_EMPTY).as_bytes())?;
     Ok(())
 }
 
@@ -0,0 +1,139 @@
+use pretty_assertions::assert_eq;
+use sexpr_out::value::{DottedList, Keyword, Symbol};
+use sexpr_out::Value;
+use sexpr_out::{sexpr, sexprs};
+
+fn symbol(s: &str) -> Value {
+    Value::from(Symbol::new(s))
+}
+
+fn keyword(s: &str) -> Value {
+    Value::from(Keyword::new(s))
+}
+
+#[test]
+fn test_sexpr_atoms() {
+    assert_eq!(sexpr! {hello}, symbol("hello"));
+    assert_eq!(sexpr! {:name}, keyword("name"));
+    assert_eq!(sexpr! {#t}, Value::from(true));
+    assert_eq!(sexpr! {#false}, Value::from(false));
+    assert_eq!(sexpr! {42}, Value::from(42));
+    assert_eq!(sexpr! {,(-1.5)}, Value::from(-1.5));
+    assert_eq!(sexpr! {'a'}, Value::from('a'));
+    assert_eq!(sexpr! {"text"}, Value::from("text"));
+    assert_eq!(sexpr! {()}, Value::empty_list());
+}
+
+#[test]
+fn test_sexpr_names() {
+    assert_eq!(sexpr! {string->list}, symbol("string->list"));
+    assert_eq!(
+        sexpr! {|with-eval-after-load|},
+        symbol("with-eval-after-load")
+    );
+    assert_eq!(sexpr! {null?}, symbol("null?"));
+    assert_eq!(sexpr! {|set-car!|}, symbol("set-car!"));
+    assert_eq!(sexpr! {|*width*|}, symbol("*width*"));
+    assert_eq!(sexpr! {:|read-only|}, keyword("read-only"));
+    assert_eq!(
+        sexpr! {(+ - * / <= >=)},
+        Value::from(vec![
+            symbol("+"),
+            symbol("-"),
+            symbol("*"),
+            symbol("/"),
+            symbol("<="),
+            symbol(">="),
+        ])
+    );
+    assert_eq!(
+        sexpr! {(_ ...)},
+        Value::from(vec![symbol("_"), symbol("...")])
+    );
+}
+
+#[test]
+fn test_sexpr_minus() {
+    assert_eq!(
+        sexpr! {(- 1 x)},
+        Value::from(vec![symbol("-"), Value::from(1), symbol("x")])
+    );
+    assert_eq!(
+        sexpr! {(a - b)},
+        Value::from(vec![symbol("a"), symbol("-"), symbol("b")])
+    );
+    assert_eq!(
+        sexpr! {(-1 ,(-1))},
+        Value::from(vec![symbol("-"), Value::from(1), Value::from(-1)])
+    );
+    assert_eq!(sexpr! {-}, symbol("-"));
+}
+
+#[test]
+fn test_sexpr_lists() {
+    assert_eq!(
+        sexpr! {(define x 10)},
+        Value::from(vec![symbol("define"), symbol("x"), Value::from(10)])
+    );
+    assert_eq!(
+        sexpr! {(list :a "b" #t (nested [1 2] #(3)))},
+        Value::from(vec![
+            symbol("list"),
+            keyword("a"),
+            Value::from("b"),
+            Value::from(true),
+            Value::from(vec![
+                symbol("nested"),
+                Value::vector(vec![Value::from(1), Value::from(2)]),
+                Value::vector(vec![Value::from(3)]),
+            ]),
+        ])
+    );
+    assert_eq!(
+        sexpr! {(a 1 . 2)},
+        Value::from(DottedList::new(vec![symbol("a"), Value::from(1)], Value::from(2)).unwrap())
+    );
+}
+
+#[test]
+fn test_sexprs_forms() {
+    assert_eq!(
+        sexprs! {(define x 10) (display x)},
+        vec![
+            Value::from(vec![symbol("define"), symbol("x"), Value::from(10)]),
+            Value::from(vec![symbol("display"), symbol("x")]),
+        ]
+    );
+    assert_eq!(sexprs! {x}, vec![symbol("x")]);
+    assert_eq!(sexprs! {}, Vec::<Value>::new());
+
+    let more = vec![symbol("b"), symbol("c")];
+    assert_eq!(
+        sexprs! {a ,@more},
+        vec![symbol("a"), symbol("b"), symbol("c")]
+    );
+}
+
+#[test]
+fn test_sexpr_interpolation() {
+    let name = Symbol::new("*width*");
+    let width = 10;
+    let args = vec![1, 2, 3];
+    assert_eq!(
+        sexpr! {(defvar ,name ,(width * 2) (list ,@{ args.clone() }) ,@{ args.iter().rev().copied() })},
+        Value::from(vec![
+            symbol("defvar"),
+            symbol("*width*"),
+            Value::from(20),
+            Value::from(vec![
+                symbol("list"),
+                Value::from(1),
+                Value::from(2),
+                Value::from(3)
+            ]),
+            Value::from(3),
+            Value::from(2),
+            Value::from(1),
+        ])
+    );
+}
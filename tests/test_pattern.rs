@@ -0,0 +1,196 @@
+use pretty_assertions::assert_eq;
+use sexpr_out::pattern::{Binding, Pattern, ValueType};
+use sexpr_out::sexpr;
+use sexpr_out::value::{Comment, Keyword, Rational, Symbol};
+#[cfg(feature = "bignum")]
+use sexpr_out::Number;
+use sexpr_out::{Error, Value};
+
+#[test]
+fn test_parse_pattern() {
+    assert_eq!(
+        Pattern::parse("(define ?name:symbol _ . ?rest)").unwrap(),
+        Pattern::DottedList(
+            vec![
+                Pattern::literal(Symbol::new("define")),
+                Pattern::typed_variable("name", ValueType::Symbol),
+                Pattern::Any,
+            ],
+            Box::new(Pattern::variable("rest"))
+        )
+    );
+    assert_eq!(
+        Pattern::parse("[#t #\\a \"s\" :kw #:kw2 -1.5 'x ?xs ...] ; comment").unwrap(),
+        Pattern::Vector(vec![
+            Pattern::literal(true),
+            Pattern::literal('a'),
+            Pattern::literal("s"),
+            Pattern::literal(Keyword::new("kw")),
+            Pattern::literal(Symbol::uninterned("kw2")),
+            Pattern::literal(-1.5),
+            Pattern::Quoted(
+                sexpr_out::value::QuoteKind::Quote,
+                Box::new(Pattern::literal(Symbol::new("x")))
+            ),
+            Pattern::repeat(Pattern::variable("xs")),
+        ])
+    );
+}
+
+#[test]
+fn test_parse_pattern_numbers() {
+    assert_eq!(
+        Pattern::parse("(1/2 -4/2 +7 1.5 1/x)").unwrap(),
+        Pattern::List(vec![
+            Pattern::literal(Rational::new(1, 2)),
+            Pattern::literal(-2),
+            Pattern::literal(7),
+            Pattern::literal(1.5),
+            Pattern::literal(Symbol::new("1/x")),
+        ])
+    );
+    assert!(Pattern::parse("1/0").is_err());
+    assert!(Pattern::parse("1/99999999999999999999").is_err());
+}
+
+#[cfg(feature = "bignum")]
+#[test]
+fn test_parse_pattern_big_integers() {
+    assert_eq!(
+        Pattern::parse("-99999999999999999999").unwrap(),
+        Pattern::literal(Number::from(-99999999999999999999_i128))
+    );
+}
+
+#[cfg(not(feature = "bignum"))]
+#[test]
+fn test_parse_pattern_big_integers() {
+    assert!(matches!(
+        Pattern::parse("-99999999999999999999"),
+        Err(Error::ParserError { .. })
+    ));
+}
+
+#[test]
+fn test_parse_pattern_errors() {
+    for s in [
+        "(a b",
+        "(a))",
+        "\"open",
+        "#z",
+        "(... a)",
+        "(a ... b ...)",
+        "(a ... . b)",
+        "?x:float",
+    ] {
+        let result = Pattern::parse(s);
+        assert!(
+            matches!(
+                result,
+                Err(Error::ParserError { .. } | Error::InvalidPattern { .. })
+            ),
+            "{s} => {result:?}"
+        );
+    }
+}
+
+#[test]
+fn test_match_variables() {
+    let pattern = Pattern::parse("(define ?name ?body ...)").unwrap();
+    assert_eq!(pattern.variables(), vec!["name", "body"]);
+
+    let bindings = pattern
+        .match_value(&sexpr!((define x (display x) (newline))))
+        .unwrap();
+    assert_eq!(bindings.value("name"), Some(&sexpr!(x)));
+    assert_eq!(
+        bindings.values("body"),
+        Some(vec![&sexpr!((display x)), &sexpr!((newline))])
+    );
+
+    let bindings = pattern.match_value(&sexpr!((define x))).unwrap();
+    assert_eq!(bindings.get("body"), Some(&Binding::Sequence(vec![])));
+
+    assert!(!pattern.is_match(&sexpr!((defun x))));
+    assert!(!pattern.is_match(&sexpr!((define))));
+}
+
+#[test]
+fn test_match_literals_and_types() {
+    let pattern = Pattern::parse("(point ?x:number ?y:number :label ?label:string)").unwrap();
+    assert!(pattern.is_match(&sexpr!((point 1 2.5 :label "a"))));
+    assert!(!pattern.is_match(&sexpr!((point 1 y :label "a"))));
+    assert!(!pattern.is_match(&sexpr!((point 1 2 :name "a"))));
+    assert!(!pattern.is_match(&sexpr!((point 1 2 :label "a" extra))));
+
+    let pattern = Pattern::parse("(?a ?b:pair ?c:atom)").unwrap();
+    assert!(pattern.is_match(&sexpr!((1(2)()))));
+    assert!(!pattern.is_match(&sexpr!((1()()))));
+}
+
+#[test]
+fn test_match_repeated_variable() {
+    let pattern = Pattern::parse("(= ?x ?x)").unwrap();
+    assert!(pattern.is_match(&sexpr!((= a a))));
+    assert!(!pattern.is_match(&sexpr!((= a b))));
+}
+
+#[test]
+fn test_match_nested_ellipsis() {
+    let pattern = Pattern::parse("(let ((?name ?value) ...) ?body ... end)").unwrap();
+    let bindings = pattern
+        .match_value(&sexpr!((let ((a 1) (b 2)) (+ a b) end)))
+        .unwrap();
+    assert_eq!(bindings.values("name"), Some(vec![&sexpr!(a), &sexpr!(b)]));
+    assert_eq!(
+        bindings.values("value"),
+        Some(vec![&Value::from(1), &Value::from(2)])
+    );
+    assert_eq!(bindings.values("body"), Some(vec![&sexpr!((+ a b))]));
+
+    let pattern = Pattern::parse("((?x ...) ...)").unwrap();
+    let bindings = pattern.match_value(&sexpr!(((1 2) () (3)))).unwrap();
+    assert_eq!(
+        bindings.get("x"),
+        Some(&Binding::Sequence(vec![
+            Binding::Sequence(vec![
+                Binding::Value(Value::from(1)),
+                Binding::Value(Value::from(2))
+            ]),
+            Binding::Sequence(vec![]),
+            Binding::Sequence(vec![Binding::Value(Value::from(3))]),
+        ]))
+    );
+}
+
+#[test]
+fn test_match_rest() {
+    let pattern = Pattern::parse("(?first . ?rest)").unwrap();
+    let bindings = pattern.match_value(&sexpr!((1 2 3))).unwrap();
+    assert_eq!(bindings.value("rest"), Some(&sexpr!((2 3))));
+
+    let bindings = pattern.match_value(&sexpr!((1 2 . 3))).unwrap();
+    assert_eq!(bindings.value("rest"), Some(&sexpr! {(2 . 3)}));
+
+    let bindings = pattern.match_value(&sexpr! {(1 . 3)}).unwrap();
+    assert_eq!(bindings.value("rest"), Some(&Value::from(3)));
+
+    assert!(!pattern.is_match(&sexpr!(())));
+}
+
+#[test]
+fn test_match_from_value() {
+    let pattern =
+        Pattern::try_from(Value::vector(vec![Symbol::new("?x").into(), sexpr!(_)])).unwrap();
+    let value = Value::vector(vec![
+        Value::from(1).with_trailing_comment(Comment::block("one")),
+        Value::from(2),
+    ]);
+    let bindings = pattern.match_value(&value).unwrap();
+    assert_eq!(bindings.len(), 1);
+    assert_eq!(
+        bindings.value("x"),
+        Some(&Value::from(1).with_trailing_comment(Comment::block("one")))
+    );
+    assert!(!pattern.is_match(&sexpr!((1 2))));
+}
@@ -0,0 +1,238 @@
+use objio::{HasOptions, ObjectWriter};
+use pretty_assertions::assert_eq;
+use sexpr_out::pattern::Pattern;
+use sexpr_out::sexpr;
+use sexpr_out::template::Template;
+use sexpr_out::value::{Comment, Keyword, Map, Shared, Struct, Symbol};
+use sexpr_out::writer::{LanguageStyle, Options, Writer};
+use sexpr_out::{Error, Value};
+use std::collections::HashMap;
+
+#[test]
+fn test_template_holes() {
+    let template = Template::parse("`(defun ,name (,@args) ,@body ,name)").unwrap();
+    assert_eq!(template.holes(), vec!["name", "args", "body"]);
+    assert_eq!(
+        Template::parse("(a `(b ,c ,,d))").unwrap().holes(),
+        vec!["d"]
+    );
+}
+
+#[test]
+fn test_template_instantiate() {
+    let template = Template::parse("(defun ,name (,@args) ,@body)").unwrap();
+    let mut values = HashMap::new();
+    values.insert("name", sexpr!(add));
+    values.insert("args", sexpr!((x y)));
+    values.insert("body", Value::vector(vec![sexpr!((+ x y))]));
+    assert_eq!(
+        template.instantiate(values).unwrap(),
+        sexpr!((defun add (x y) (+ x y)))
+    );
+
+    let template = Template::parse("(,@items . ,tail)").unwrap();
+    assert_eq!(
+        template
+            .instantiate([("items", sexpr!(())), ("tail", sexpr!(rest))])
+            .unwrap(),
+        sexpr!(rest)
+    );
+    let template = Template::parse("(config ,@items . ,tail)").unwrap();
+    assert_eq!(
+        template
+            .instantiate([("items", sexpr!((1 2))), ("tail", sexpr!(rest))])
+            .unwrap(),
+        sexpr! {(config 1 2 . rest)}
+    );
+}
+
+#[test]
+fn test_template_nested_quasiquote() {
+    let template = Template::parse("(defmacro ,name () `(list ,x ,,value))").unwrap();
+    let value = template
+        .instantiate([("name", sexpr!(m)), ("value", Value::from(1))])
+        .unwrap();
+    assert_eq!(
+        value,
+        Value::from(vec![
+            sexpr!(defmacro),
+            sexpr!(m),
+            sexpr!(()),
+            Value::quasi_quote(Value::from(vec![
+                sexpr!(list),
+                Value::unquote(sexpr!(x)),
+                Value::unquote(Value::from(1)),
+            ])),
+        ])
+    );
+}
+
+#[test]
+fn test_template_keeps_comments() {
+    let template = Template::new(Value::from(vec![
+        Value::from(Symbol::new("setq")),
+        Value::unquote(Symbol::new("name")).with_trailing_comment(Comment::block("the variable")),
+    ]))
+    .unwrap();
+    assert_eq!(
+        template.instantiate([("name", sexpr!(x))]).unwrap(),
+        Value::from(vec![
+            Value::from(Symbol::new("setq")),
+            sexpr!(x).with_trailing_comment(Comment::block("the variable")),
+        ])
+    );
+}
+
+#[test]
+fn test_template_keeps_splice_comments() {
+    let template = Template::new(Value::from(vec![
+        Value::from(Symbol::new("progn")),
+        Value::unquote_splicing(Symbol::new("body"))
+            .with_leading_comment(Comment::block("the body")),
+    ]))
+    .unwrap();
+    assert_eq!(
+        template.instantiate([("body", sexpr!((a b)))]).unwrap(),
+        Value::from(vec![
+            Value::from(Symbol::new("progn")),
+            sexpr!(a).with_leading_comment(Comment::block("the body")),
+            sexpr!(b),
+        ])
+    );
+    assert!(matches!(
+        template.instantiate([("body", sexpr!(()))]),
+        Err(Error::InvalidTemplate { .. })
+    ));
+}
+
+#[test]
+fn test_template_fills_map_keys_without_duplicates() {
+    let mut map = Map::new();
+    map.insert(Value::unquote(Symbol::new("k1")), 1);
+    map.insert(Value::unquote(Symbol::new("k2")), 2);
+    let template = Template::new(Value::from(map)).unwrap();
+
+    let mut expected = Map::new();
+    expected.insert(Keyword::new("a"), 2);
+    assert_eq!(
+        template
+            .instantiate([("k1", sexpr!(:a)), ("k2", sexpr!(:a))])
+            .unwrap(),
+        Value::from(expected)
+    );
+}
+
+#[test]
+fn test_template_fills_containers() {
+    let mut map = Map::new();
+    map.insert(
+        Value::unquote(Symbol::new("key")),
+        Value::unquote(Symbol::new("value")),
+    );
+    let template = Template::new(Value::from(vec![
+        Value::from(map),
+        Struct::new(
+            Symbol::new("point"),
+            [Value::unquote(Symbol::new("x")), Value::from(2)],
+        )
+        .into(),
+        Value::boxed(Value::unquote(Symbol::new("x"))),
+    ]))
+    .unwrap();
+    assert_eq!(template.holes(), vec!["key", "value", "x"]);
+
+    let mut expected = Map::new();
+    expected.insert(Keyword::new("a"), 1);
+    assert_eq!(
+        template
+            .instantiate([("key", sexpr!(:a)), ("value", sexpr!(1)), ("x", sexpr!(0))])
+            .unwrap(),
+        Value::from(vec![
+            Value::from(expected),
+            Struct::new(Symbol::new("point"), [Value::from(0), Value::from(2)]).into(),
+            Value::boxed(0),
+        ])
+    );
+}
+
+#[test]
+fn test_template_shared_values() {
+    let shared = Value::from(Shared::new(sexpr!((a b))));
+    let template = Template::new(Value::from(vec![
+        shared.clone(),
+        Value::unquote(Symbol::new("x")),
+    ]))
+    .unwrap();
+    assert_eq!(
+        template.instantiate([("x", 1)]).unwrap(),
+        Value::from(vec![shared, Value::from(1)])
+    );
+
+    let shared = Value::from(Shared::new(vec![
+        sexpr!(a),
+        Value::unquote(Symbol::new("x")),
+    ]));
+    assert!(matches!(
+        Template::new(Value::from(vec![shared])),
+        Err(Error::InvalidTemplate { .. })
+    ));
+}
+
+#[test]
+fn test_template_errors() {
+    assert!(matches!(
+        Template::parse("(a ,(b c))"),
+        Err(Error::InvalidTemplate { .. })
+    ));
+    assert!(matches!(
+        Template::parse(",@a"),
+        Err(Error::InvalidTemplate { .. })
+    ));
+    assert!(matches!(
+        Template::parse("(a . ,@b)"),
+        Err(Error::InvalidTemplate { .. })
+    ));
+
+    let template = Template::parse("(a ,b ,@c)").unwrap();
+    assert!(matches!(
+        template.instantiate([("b", 1)]),
+        Err(Error::MissingTemplateValue { name }) if name == "c"
+    ));
+    assert!(matches!(
+        template.instantiate([("b", 1), ("c", 2)]),
+        Err(Error::InvalidTemplate { .. })
+    ));
+}
+
+#[test]
+fn test_template_from_pattern() {
+    let pattern = Pattern::parse("(defvar ?name ?value :doc ?doc)").unwrap();
+    let template = Template::parse("(defvar ,name ,value ,doc)").unwrap();
+    let bindings = pattern
+        .match_value(&sexpr!((defvar width 10 :doc "The width.")))
+        .unwrap();
+    let value = template.instantiate(bindings).unwrap();
+
+    let writer =
+        Writer::default().with_options(Options::default().with_style(LanguageStyle::EmacsLisp));
+    assert_eq!(
+        writer.write_to_string(&value).unwrap(),
+        "(defvar width 10 \"The width.\")"
+    );
+}
+
+#[test]
+fn test_template_reads_written_symbols() {
+    let value = Value::from(vec![
+        Value::from(Symbol::new("let")),
+        Value::from(Symbol::uninterned("g1")),
+        Value::from(Keyword::new("key")),
+    ]);
+
+    for style in [LanguageStyle::CommonLisp, LanguageStyle::EmacsLisp] {
+        let writer = Writer::default().with_options(Options::default().with_style(style));
+        let written = writer.write_to_string(&value).unwrap();
+        assert_eq!(written, "(let #:g1 :key)");
+        assert_eq!(Template::parse(&written).unwrap().value(), &value);
+    }
+}
@@ -0,0 +1,490 @@
+use pretty_assertions::assert_eq;
+use sexpr_out::sexpr;
+use sexpr_out::value::{
+    Array, Comment, DottedList, Keyword, Map, MapTest, PropertizedString, Rational, Selector,
+    Shared, Symbol, SymbolQualifier, TextProperties, Value,
+};
+use sexpr_out::writer::LanguageStyle;
+use std::collections::HashSet;
+
+#[test]
+fn test_parse_symbol_valid() {
+    for (s, style) in [
+        ("hello-world", LanguageStyle::Racket),
+        ("string->list", LanguageStyle::Scheme),
+        ("1+", LanguageStyle::CommonLisp),
+        ("with-eval-after-load", LanguageStyle::EmacsLisp),
+        ("source_file", LanguageStyle::TreeSitter),
+        ("λ", LanguageStyle::Racket),
+        ("#%app", LanguageStyle::Racket),
+        ("+", LanguageStyle::Racket),
+        ("-i-", LanguageStyle::Scheme),
+        ("e10", LanguageStyle::Racket),
+        ("+inf.0", LanguageStyle::CommonLisp),
+        ("1+2i", LanguageStyle::EmacsLisp),
+    ] {
+        let symbol = Symbol::parse(s, style).unwrap();
+        assert_eq!(symbol.as_ref(), s);
+        assert!(!symbol.is_qualified());
+    }
+}
+
+#[test]
+fn test_parse_symbol_invalid() {
+    for (s, style) in [
+        ("", LanguageStyle::Racket),
+        ("a b", LanguageStyle::Racket),
+        ("a(b", LanguageStyle::Scheme),
+        ("a|b", LanguageStyle::CommonLisp),
+        ("123", LanguageStyle::Racket),
+        ("-1.5", LanguageStyle::EmacsLisp),
+        ("1/2", LanguageStyle::Scheme),
+        ("-1/99999999999999999999", LanguageStyle::Racket),
+        ("1+2i", LanguageStyle::Racket),
+        ("-i", LanguageStyle::Scheme),
+        ("1.5e+3-inf.0i", LanguageStyle::Racket),
+        ("1@2", LanguageStyle::Scheme),
+        ("+inf.0", LanguageStyle::Racket),
+        ("-nan.0", LanguageStyle::Scheme),
+        ("+inf.f", LanguageStyle::Racket),
+        ("1d0", LanguageStyle::CommonLisp),
+        ("1.0e+INF", LanguageStyle::EmacsLisp),
+        ("#%app", LanguageStyle::Scheme),
+        ("Foo", LanguageStyle::CommonLisp),
+        (".", LanguageStyle::Racket),
+        ("#t", LanguageStyle::Racket),
+        ("?a", LanguageStyle::EmacsLisp),
+        ("[a]", LanguageStyle::EmacsLisp),
+        ("a[b]", LanguageStyle::Scheme),
+        (":key", LanguageStyle::CommonLisp),
+        ("a:b:c", LanguageStyle::CommonLisp),
+        ("source-file", LanguageStyle::TreeSitter),
+        ("1st", LanguageStyle::TreeSitter),
+    ] {
+        assert!(
+            Symbol::parse(s, style).is_err(),
+            "expected {s:?} to be invalid in {style:?}"
+        );
+    }
+    assert!(Symbol::parse("Foo", LanguageStyle::CommonLisp)
+        .unwrap_err()
+        .to_string()
+        .contains("the reader upcases unescaped names"));
+}
+
+#[test]
+fn test_parse_symbol_qualified() {
+    let symbol = Symbol::parse("cl:car", LanguageStyle::CommonLisp).unwrap();
+    assert_eq!(
+        symbol.qualifier(),
+        Some(&SymbolQualifier::External("cl".to_string()))
+    );
+    assert_eq!(symbol.as_ref(), "car");
+
+    let symbol = Symbol::parse("my-pkg::helper", LanguageStyle::CommonLisp).unwrap();
+    assert_eq!(
+        symbol.qualifier(),
+        Some(&SymbolQualifier::Internal("my-pkg".to_string()))
+    );
+    assert_eq!(symbol.as_ref(), "helper");
+
+    let symbol = Symbol::parse("#:g1", LanguageStyle::EmacsLisp).unwrap();
+    assert_eq!(symbol.qualifier(), Some(&SymbolQualifier::Uninterned));
+    assert_eq!(symbol.as_ref(), "g1");
+
+    assert!(Symbol::parse("cl:car", LanguageStyle::Racket).is_ok());
+    assert!(Symbol::parse("#:g1", LanguageStyle::Racket).is_err());
+}
+
+#[test]
+fn test_parse_keyword() {
+    assert_eq!(
+        Keyword::parse("width", LanguageStyle::CommonLisp)
+            .unwrap()
+            .inner()
+            .as_ref(),
+        "width"
+    );
+    assert!(Keyword::parse(":width", LanguageStyle::CommonLisp).is_err());
+    assert!(Keyword::parse("field_name", LanguageStyle::TreeSitter).is_ok());
+    assert!(Keyword::parse("field-name", LanguageStyle::TreeSitter).is_err());
+
+    assert_eq!(
+        Keyword::try_from(Symbol::new("width")).unwrap(),
+        Keyword::new("width")
+    );
+    assert!(Keyword::try_from(Symbol::external("pkg", "width")).is_err());
+}
+
+#[cfg(feature = "normalization")]
+#[test]
+fn test_parse_symbol_normalized() {
+    let decomposed = "cafe\u{0301}";
+    let symbol = Symbol::parse_normalized(decomposed, LanguageStyle::Racket).unwrap();
+    assert_eq!(symbol.as_ref(), "caf\u{00E9}");
+    assert_eq!(
+        Symbol::parse(decomposed, LanguageStyle::Racket)
+            .unwrap()
+            .as_ref(),
+        decomposed
+    );
+}
+
+#[test]
+#[allow(clippy::mutable_key_type)] // Shared hashes by pointer, not by its contents.
+fn test_value_eq_and_hash() {
+    let values: HashSet<Value> = [
+        Value::from(1),
+        Value::from(1.0),
+        Value::from(1),
+        Value::from(f64::NAN),
+        Value::from(f64::NAN),
+        Value::from(Symbol::new("a")),
+        Value::from(Keyword::new("a")),
+        Value::from(vec![Value::from(1), Value::from("a")]),
+        Value::from(vec![Value::from(1), Value::from("a")]),
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(values.len(), 6);
+    assert_ne!(Value::from(0.0), Value::from(-0.0));
+    assert_ne!(Value::from(1), Value::from(1.0));
+}
+
+#[cfg(feature = "bignum")]
+#[test]
+fn test_integer_kinds_compare_by_value() {
+    use num_bigint::BigInt;
+    use sexpr_out::Number;
+
+    let small = Number::Integer(5);
+    let big = Number::BigInteger(BigInt::from(5));
+    assert_eq!(small, big);
+    assert_eq!(
+        [small.clone(), big.clone()]
+            .into_iter()
+            .collect::<HashSet<_>>()
+            .len(),
+        1
+    );
+    assert!(Number::Integer(6) > big);
+    assert!(Number::from(u64::MAX) > Number::Integer(i64::MAX));
+    assert!(Number::from(u64::MAX) < Number::from(Rational::new(1, 2)));
+
+    assert!(big.is_integer());
+    assert_eq!(big.as_integer(), Some(5));
+    assert_eq!(Value::from(big).as_integer(), Some(5));
+    assert!(Number::from(u64::MAX).is_integer());
+    assert_eq!(Number::from(u64::MAX).as_integer(), None);
+    assert!(!Number::from(Rational::new(1, 2)).is_integer());
+}
+
+#[test]
+fn test_value_ordering() {
+    let mut numbers = vec![
+        Value::from(f64::NAN),
+        Value::from(1.5),
+        Value::from(0.0),
+        Value::from(-0.0),
+        Value::from(f64::NEG_INFINITY),
+        Value::from(2),
+        Value::from(-3),
+    ];
+    numbers.sort();
+    assert_eq!(
+        numbers,
+        vec![
+            Value::from(-3),
+            Value::from(2),
+            Value::from(f64::NEG_INFINITY),
+            Value::from(-0.0),
+            Value::from(0.0),
+            Value::from(1.5),
+            Value::from(f64::NAN),
+        ]
+    );
+
+    let mut symbols = vec![Symbol::new("c"), Symbol::new("a"), Symbol::new("b")];
+    symbols.sort();
+    assert_eq!(
+        symbols,
+        vec![Symbol::new("a"), Symbol::new("b"), Symbol::new("c")]
+    );
+}
+
+#[test]
+fn test_value_equivalence() {
+    let list = Value::from(vec![Value::from(1), Value::from("a")]);
+    let copy = list.clone();
+    assert!(list.is_eq(&list));
+    assert!(!list.is_eq(&copy));
+    assert!(!list.is_eqv(&copy));
+    assert!(list.is_equal(&copy));
+
+    assert!(Value::from(Symbol::new("a")).is_eq(&Value::from(Symbol::new("a"))));
+    let uninterned = Value::from(Symbol::uninterned("g1"));
+    assert!(uninterned.is_eq(&uninterned));
+    assert!(!uninterned.is_eq(&Value::from(Symbol::uninterned("g1"))));
+    assert!(!uninterned.is_eqv(&Value::from(Symbol::uninterned("g1"))));
+    let shared = Value::from(Shared::new(uninterned));
+    assert!(shared.is_eq(&shared.clone()));
+    assert!(Value::empty_list().is_eq(&Value::empty_list()));
+    assert!(!Value::from(2.5).is_eq(&Value::from(2.5)));
+    assert!(Value::from(2.5).is_eqv(&Value::from(2.5)));
+    assert!(!Value::from(1).is_eqv(&Value::from(1.0)));
+    assert!(!Value::from("a").is_eqv(&Value::from("a")));
+    assert!(Value::from("a").is_equal(&Value::from("a")));
+
+    let commented = Value::from(1).with_leading_comment(Comment::block("one"));
+    assert_ne!(commented, Value::from(1));
+    assert!(commented.is_equal(&Value::from(1)));
+
+    for test in [MapTest::Eq, MapTest::Eqv, MapTest::Equal] {
+        assert!(Value::from(true).is_equivalent(&Value::from(true), test));
+    }
+    assert!(!list.is_equivalent(&copy, MapTest::Eqv));
+    assert!(list.is_equivalent(&copy, MapTest::Equal));
+}
+
+#[test]
+fn test_value_equal_maps() {
+    let forward: Map = [("a", 1), ("b", 2)].into_iter().collect();
+    let backward: Map = [("b", 2), ("a", 1)].into_iter().collect();
+    assert!(Value::from(&forward).is_equal(&Value::from(&backward)));
+
+    let different: Map = [("b", 2), ("a", 3)].into_iter().collect();
+    assert!(!Value::from(&forward).is_equal(&Value::from(different)));
+    assert!(!Value::from(&forward).is_equal(&Value::from(backward.with_test(MapTest::Eqv))));
+}
+
+#[test]
+fn test_value_equal_shared() {
+    let shared = Shared::new(Value::from(vec![Value::from(1)]));
+    assert!(Value::from(shared.clone()).is_eq(&Value::from(shared.clone())));
+    assert!(Value::from(shared).is_equal(&Value::from(vec![Value::from(1)])));
+
+    let cycle = |n: i64| {
+        let shared = Shared::empty();
+        shared
+            .set(Value::from(vec![
+                Value::from(n),
+                Value::from(shared.clone()),
+            ]))
+            .unwrap();
+        Value::from(shared)
+    };
+    assert!(cycle(1).is_equal(&cycle(1)));
+    assert!(!cycle(1).is_equal(&cycle(2)));
+    assert!(!cycle(1).is_eqv(&cycle(1)));
+}
+
+#[test]
+fn test_value_accessors() {
+    let value = sexpr!((define x "text" :key 10 #t));
+    assert!(value.is_list());
+    assert_eq!(value.as_list().map(Vec::len), Some(6));
+    assert_eq!(value[0].as_symbol(), Some(&Symbol::new("define")));
+    assert_eq!(value[2].as_str(), Some("text"));
+    assert!(value[3].is_keyword());
+    assert_eq!(value[4].as_integer(), Some(10));
+    assert_eq!(value[5].as_bool(), Some(true));
+    assert_eq!(value.get(6), None);
+    assert_eq!(value[1].as_str(), None);
+    assert!(sexpr!(()).is_empty_list());
+
+    let commented = Value::from("text").with_leading_comment(Comment::block("note"));
+    assert_eq!(commented.as_str(), None);
+    assert_eq!(commented.resolved().as_str(), Some("text"));
+}
+
+#[test]
+fn test_value_improper_list() {
+    assert!(DottedList::new(vec![], Value::from(1)).is_err());
+    assert_eq!(
+        DottedList::new(vec![Value::from(1)], Value::from(2)).unwrap(),
+        DottedList::pair(1, 2)
+    );
+    assert_eq!(Value::improper_list(vec![], Value::from(1)), Value::from(1));
+    assert!(Value::improper_list(vec![Value::from(1)], Value::from(2)).is_dotted_list());
+}
+
+#[test]
+fn test_value_lookup() {
+    let alist = Value::from(vec![
+        Value::pair(Symbol::new("a"), 1),
+        Value::pair(Symbol::new("b"), 2),
+    ]);
+    assert_eq!(alist.assoc(&sexpr!(b)), Some(&Value::from(2)));
+    assert_eq!(alist.assoc(&sexpr!(c)), None);
+
+    let plist = sexpr!((:name "x" :width 10));
+    assert_eq!(
+        plist.plist_get(&Keyword::new("width")),
+        Some(&Value::from(10))
+    );
+    assert_eq!(plist.plist_get(&Keyword::new("height")), None);
+
+    let mut map = Map::new().with_test(MapTest::Eqv);
+    map.insert(1, "one");
+    map.insert(1.0, "one point zero");
+    let map = Value::from(map);
+    assert_eq!(
+        map.assoc(&Value::from(1.0)),
+        Some(&Value::from("one point zero"))
+    );
+}
+
+#[test]
+fn test_rational_try_new() {
+    let third = Rational::try_new(2, -6).unwrap();
+    assert_eq!((third.numerator(), third.denominator()), (-1, 3));
+    assert_eq!(Rational::try_from((4, 2)).unwrap(), Rational::new(2, 1));
+    assert!(Rational::try_new(1, 0).is_err());
+    assert!(Rational::try_new(i64::MIN, -1).is_err());
+    assert!(Rational::try_new(1, i64::MIN).is_err());
+    assert_eq!(
+        Rational::try_new(i64::MIN, i64::MIN).unwrap(),
+        Rational::new(1, 1)
+    );
+    assert_eq!(
+        Rational::try_new(i64::MIN, 2).unwrap().numerator(),
+        i64::MIN / 2
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_rational_new_zero_denominator() {
+    let _ = Rational::new(1, 0);
+}
+
+#[test]
+fn test_array_new() {
+    let array = Array::new(vec![2, 3], (0..6).map(Value::from)).unwrap();
+    assert_eq!(array.rank(), 2);
+    assert!(Array::new(vec![2, 3], (0..5).map(Value::from)).is_err());
+    assert!(Array::new(vec![usize::MAX, 2], []).is_err());
+}
+
+#[test]
+fn test_text_properties_range() {
+    assert!(TextProperties::new(3, 2).is_err());
+
+    let text = PropertizedString::new("héllo");
+    assert!(text
+        .clone()
+        .with_properties(TextProperties::new(0, 5).unwrap())
+        .is_ok());
+    assert!(text
+        .with_properties(TextProperties::new(2, 6).unwrap())
+        .is_err());
+}
+
+#[test]
+fn test_map_insert_replaces() {
+    let mut map = Map::new();
+    assert_eq!(map.insert(Keyword::new("a"), 1), None);
+    assert_eq!(map.insert(Keyword::new("b"), 2), None);
+    assert_eq!(map.insert(Keyword::new("a"), 3), Some(Value::from(1)));
+    assert_eq!(map.len(), 2);
+    assert_eq!(
+        map.entries(),
+        &vec![
+            (Keyword::new("a").into(), Value::from(3)),
+            (Keyword::new("b").into(), Value::from(2)),
+        ]
+    );
+}
+
+#[test]
+fn test_value_get_in() {
+    let mut value = sexpr!((config (:name "x" :sizes [1 2 3])));
+    let path = [
+        Selector::index(1),
+        Selector::property(Keyword::new("sizes")),
+        Selector::index(2),
+    ];
+    assert_eq!(value.get_in(&path), Some(&Value::from(3)));
+    assert_eq!(value.get_in(&[]), Some(&value));
+    assert_eq!(value.get_in(&[Selector::index(3)]), None);
+
+    *value.get_in_mut(&path).unwrap() = Value::from(4);
+    assert_eq!(value, sexpr!((config (:name "x" :sizes [1 2 4]))));
+}
+
+#[test]
+fn test_value_path_edits() {
+    let mut value = sexpr!((config (:name "x") ()));
+
+    value
+        .insert_at(
+            &[
+                Selector::index(1),
+                Selector::property(Keyword::new("width")),
+            ],
+            10,
+        )
+        .unwrap();
+    value
+        .insert_at(&[Selector::index(2), Selector::key(Symbol::new("a"))], 1)
+        .unwrap();
+    value.insert_at(&[Selector::index(3)], sexpr!(end)).unwrap();
+    assert_eq!(
+        value.insert_at(&[Selector::index(9)], 0),
+        Err(Value::from(0))
+    );
+    assert!(value
+        .insert_at(
+            &[
+                Selector::index(1),
+                Selector::property(Keyword::new("width"))
+            ],
+            0
+        )
+        .is_err());
+    assert_eq!(
+        value,
+        Value::from(vec![
+            sexpr!(config),
+            sexpr!((:name "x" :width 10)),
+            Value::from(vec![Value::pair(Symbol::new("a"), 1)]),
+            sexpr!(end),
+        ])
+    );
+
+    assert_eq!(
+        value.replace_at(
+            &[Selector::index(1), Selector::property(Keyword::new("name"))],
+            "y"
+        ),
+        Ok(Value::from("x"))
+    );
+    assert_eq!(
+        value.remove_at(&[Selector::index(2), Selector::key(Symbol::new("a"))]),
+        Some(Value::from(1))
+    );
+    assert_eq!(
+        value.remove_at(&[
+            Selector::index(1),
+            Selector::property(Keyword::new("width"))
+        ]),
+        Some(Value::from(10))
+    );
+    assert_eq!(value.remove_at(&[Selector::index(3)]), Some(sexpr!(end)));
+    assert_eq!(value.remove_at(&[Selector::index(3)]), None);
+    assert_eq!(value, sexpr!((config (:name "y") ())));
+}
+
+#[test]
+fn test_value_replace_keeps_comments() {
+    let mut value = Value::from(vec![
+        Value::from(1).with_trailing_comment(Comment::line(Default::default(), "one"))
+    ]);
+    assert_eq!(value.replace_at(&[0.into()], 2), Ok(Value::from(1)));
+    assert_eq!(
+        value,
+        Value::from(vec![
+            Value::from(2).with_trailing_comment(Comment::line(Default::default(), "one"))
+        ])
+    );
+}
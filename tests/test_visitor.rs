@@ -0,0 +1,153 @@
+use pretty_assertions::assert_eq;
+use sexpr_out::sexpr;
+use sexpr_out::value::{Comment, Symbol};
+use sexpr_out::visitor::{fold, walk, walk_mut, Visit, Visitor, VisitorMut};
+use sexpr_out::Value;
+
+#[derive(Default)]
+struct Trace(Vec<String>);
+
+impl Visitor for Trace {
+    fn enter(&mut self, value: &Value) -> Visit {
+        if let Some(symbol) = value.as_symbol() {
+            self.0.push(format!("enter {}", symbol.as_ref()));
+        } else if value.is_list() {
+            self.0.push("enter list".to_string());
+        }
+        if value.as_symbol() == Some(&Symbol::new("stop")) {
+            Visit::Stop
+        } else if value.is_quoted() {
+            Visit::SkipChildren
+        } else {
+            Visit::Continue
+        }
+    }
+
+    fn leave(&mut self, value: &Value) -> Visit {
+        if value.is_list() {
+            self.0.push("leave list".to_string());
+        }
+        Visit::Continue
+    }
+}
+
+#[test]
+fn test_walk_order() {
+    let mut trace = Trace::default();
+    let value = Value::from(vec![
+        sexpr!(a),
+        sexpr!((b c)),
+        Value::quote(sexpr!(hidden)),
+        sexpr!(d),
+    ]);
+    assert_eq!(walk(&value, &mut trace), Visit::Continue);
+    assert_eq!(
+        trace.0,
+        vec![
+            "enter list",
+            "enter a",
+            "enter list",
+            "enter b",
+            "enter c",
+            "leave list",
+            "enter d",
+            "leave list",
+        ]
+    );
+}
+
+#[test]
+fn test_walk_stop() {
+    let mut trace = Trace::default();
+    assert_eq!(walk(&sexpr!((a (stop b) c)), &mut trace), Visit::Stop);
+    assert_eq!(
+        trace.0,
+        vec!["enter list", "enter a", "enter list", "enter stop"]
+    );
+}
+
+struct StripKeywords;
+
+impl VisitorMut for StripKeywords {
+    fn enter(&mut self, value: &mut Value) -> Visit {
+        if let Some(items) = value.as_list_mut() {
+            items.retain(|item| !item.is_keyword());
+        }
+        Visit::Continue
+    }
+}
+
+struct DoubleIntegers;
+
+impl VisitorMut for DoubleIntegers {
+    fn leave(&mut self, value: &mut Value) -> Visit {
+        if let Some(v) = value.as_integer() {
+            *value = Value::from(v * 2);
+        }
+        Visit::Continue
+    }
+}
+
+#[test]
+fn test_walk_mut() {
+    let mut value = sexpr!((widget :name "x" (size :width 10 :height 20) [1 (2 . 3)]));
+    assert_eq!(walk_mut(&mut value, &mut StripKeywords), Visit::Continue);
+    assert_eq!(walk_mut(&mut value, &mut DoubleIntegers), Visit::Continue);
+    assert_eq!(value, sexpr!((widget "x" (size 20 40) [2 (4 . 6)])));
+}
+
+#[test]
+fn test_walk_mut_keeps_comments() {
+    let mut value = Value::from(vec![
+        Value::from(1).with_leading_comment(Comment::block("one")),
+        Value::quote(2),
+    ]);
+    walk_mut(&mut value, &mut DoubleIntegers);
+    assert_eq!(
+        value,
+        Value::from(vec![
+            Value::from(2).with_leading_comment(Comment::block("one")),
+            Value::quote(4),
+        ])
+    );
+}
+
+struct StopAtThree;
+
+impl VisitorMut for StopAtThree {
+    fn enter(&mut self, value: &mut Value) -> Visit {
+        match value.as_integer() {
+            Some(3) => Visit::Stop,
+            Some(v) => {
+                *value = Value::from(v * 10);
+                Visit::Continue
+            }
+            None => Visit::Continue,
+        }
+    }
+}
+
+#[test]
+fn test_walk_mut_stop() {
+    let mut value = sexpr!((1 (2 3 4) 5));
+    assert_eq!(walk_mut(&mut value, &mut StopAtThree), Visit::Stop);
+    assert_eq!(value, sexpr!((10 (20 3 4) 5)));
+}
+
+#[test]
+fn test_fold() {
+    let value = sexpr!((define (f x) (+ x 1)));
+    let count = fold(&value, |_, children: Vec<usize>| {
+        1 + children.into_iter().sum::<usize>()
+    });
+    assert_eq!(count, 9);
+
+    let symbols = fold(&value, |value, children: Vec<Vec<String>>| {
+        let mut symbols: Vec<String> = children.into_iter().flatten().collect();
+        if let Some(symbol) = value.as_symbol() {
+            symbols.push(symbol.as_ref().to_string());
+        }
+        symbols
+    });
+    assert_eq!(symbols, vec!["define", "f", "x", "+", "x"]);
+}
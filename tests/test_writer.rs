@@ -1,7 +1,14 @@
 use objio::{HasOptions, ObjectWriter};
 use pretty_assertions::assert_eq;
-use sexpr_out::value::Value;
-use sexpr_out::writer::{LanguageStyle, Options, Writer};
+use sexpr_out::value::{
+    Array, CharModifier, Comment, Complex, DatumLabel, Keyword, LineCommentLevel, Map, MapTest,
+    ModifiedChar, Node, PropertizedString, Rational, Regexp, RegexpSyntax, Shared, Struct, Symbol,
+    TextProperties, Value,
+};
+use sexpr_out::writer::{
+    DefinitionKind, LanguageStyle, MapFallback, Options, QuoteStyle, RationalFallback, Writer,
+};
+use std::path::PathBuf;
 
 #[test]
 fn test_print_single_boolean_racket() {
@@ -291,3 +298,1250 @@ fn test_print_empty_list() {
         );
     }
 }
+
+#[test]
+fn test_print_dotted_pair() {
+    for style in [
+        LanguageStyle::Racket,
+        LanguageStyle::CommonLisp,
+        LanguageStyle::Scheme,
+        LanguageStyle::EmacsLisp,
+    ] {
+        let writer = Writer::default().with_options(Options::default().with_style(style));
+
+        assert_eq!(
+            writer
+                .write_to_string(&Value::pair(Value::from(1), Value::from(2)))
+                .unwrap(),
+            r"(1 . 2)".to_string()
+        );
+    }
+}
+
+#[test]
+fn test_print_improper_list() {
+    let writer =
+        Writer::default().with_options(Options::default().with_style(LanguageStyle::Scheme));
+
+    assert_eq!(
+        writer
+            .write_to_string(&Value::improper_list(
+                vec![Value::from(1), Value::from(2)],
+                Value::from(3)
+            ))
+            .unwrap(),
+        r"(1 2 . 3)".to_string()
+    );
+
+    // nested pairs are flattened, and a list tail makes a proper list.
+    assert_eq!(
+        writer
+            .write_to_string(&Value::pair(
+                Value::from(1),
+                Value::pair(Value::from(2), Value::from(3))
+            ))
+            .unwrap(),
+        r"(1 2 . 3)".to_string()
+    );
+    assert_eq!(
+        writer
+            .write_to_string(&Value::pair(
+                Value::from(1),
+                Value::from(vec![Value::from(2), Value::from(3)])
+            ))
+            .unwrap(),
+        r"(1 2 3)".to_string()
+    );
+}
+
+#[test]
+fn test_print_alist_racket() {
+    let writer =
+        Writer::default().with_options(Options::default().with_style(LanguageStyle::Racket));
+
+    let alist = Value::from(vec![
+        Value::pair(Value::from("a"), Value::from(1)),
+        Value::pair(Value::from("b"), Value::from(2)),
+    ]);
+
+    assert_eq!(
+        writer.write_to_string(&alist).unwrap(),
+        r#"(("a" . 1) ("b" . 2))"#.to_string()
+    );
+}
+
+#[test]
+fn test_print_dotted_pair_treesitter_is_error() {
+    let writer =
+        Writer::default().with_options(Options::default().with_style(LanguageStyle::TreeSitter));
+
+    assert!(writer
+        .write_to_string(&Value::pair(Value::from(1), Value::from(2)))
+        .is_err());
+    assert!(writer
+        .write_to_string(&Value::from(vec![Value::pair(
+            Value::from(1),
+            Value::from(2)
+        )]))
+        .is_err());
+}
+
+#[test]
+fn test_print_vector() {
+    let vector = Value::vector([Value::from(1), Value::from(2), Value::from(3)]);
+
+    for (style, expected) in [
+        (LanguageStyle::Racket, "#(1 2 3)"),
+        (LanguageStyle::CommonLisp, "#(1 2 3)"),
+        (LanguageStyle::Scheme, "#(1 2 3)"),
+        (LanguageStyle::EmacsLisp, "[1 2 3]"),
+    ] {
+        let writer = Writer::default().with_options(Options::default().with_style(style));
+
+        assert_eq!(
+            writer.write_to_string(&vector).unwrap(),
+            expected.to_string()
+        );
+    }
+}
+
+#[test]
+fn test_print_empty_vector() {
+    let writer =
+        Writer::default().with_options(Options::default().with_style(LanguageStyle::Scheme));
+
+    assert_eq!(
+        writer.write_to_string(&Value::empty_vector()).unwrap(),
+        "#()".to_string()
+    );
+}
+
+#[test]
+fn test_print_vector_treesitter_is_error() {
+    let writer =
+        Writer::default().with_options(Options::default().with_style(LanguageStyle::TreeSitter));
+
+    assert!(writer.write_to_string(&Value::empty_vector()).is_err());
+}
+
+#[test]
+fn test_print_map_racket() {
+    let map = Map::from_iter([("a", 1), ("b", 2)]);
+
+    let writer =
+        Writer::default().with_options(Options::default().with_style(LanguageStyle::Racket));
+
+    assert_eq!(
+        writer.write_to_string(&Value::from(map.clone())).unwrap(),
+        r#"#hash(("a" . 1) ("b" . 2))"#.to_string()
+    );
+    assert_eq!(
+        writer
+            .write_to_string(&Value::from(map.clone().with_test(MapTest::Eqv)))
+            .unwrap(),
+        r#"#hasheqv(("a" . 1) ("b" . 2))"#.to_string()
+    );
+    assert_eq!(
+        writer
+            .write_to_string(&Value::from(map.with_test(MapTest::Eq)))
+            .unwrap(),
+        r#"#hasheq(("a" . 1) ("b" . 2))"#.to_string()
+    );
+}
+
+#[test]
+fn test_print_map_elisp() {
+    let map = Map::from_iter([("a", 1), ("b", 2)]);
+
+    let writer =
+        Writer::default().with_options(Options::default().with_style(LanguageStyle::EmacsLisp));
+
+    assert_eq!(
+        writer.write_to_string(&Value::from(map)).unwrap(),
+        r#"#s(hash-table test equal data ("a" 1 "b" 2))"#.to_string()
+    );
+}
+
+#[test]
+fn test_print_map_fallback() {
+    let mut map = Map::new();
+    map.insert(Keyword::new("a"), 1);
+    map.insert(Keyword::new("b"), 2);
+    let map = Value::from(map);
+
+    let writer =
+        Writer::default().with_options(Options::default().with_style(LanguageStyle::CommonLisp));
+    assert_eq!(
+        writer.write_to_string(&map).unwrap(),
+        r#"((:a . 1) (:b . 2))"#.to_string()
+    );
+
+    let writer = Writer::default().with_options(
+        Options::default()
+            .with_style(LanguageStyle::CommonLisp)
+            .with_map_fallback(MapFallback::PropertyList),
+    );
+    assert_eq!(
+        writer.write_to_string(&map).unwrap(),
+        r#"(:a 1 :b 2)"#.to_string()
+    );
+
+    let writer =
+        Writer::default().with_options(Options::default().with_style(LanguageStyle::TreeSitter));
+    assert!(writer.write_to_string(&map).is_err());
+}
+
+#[test]
+fn test_print_symbol() {
+    let writer =
+        Writer::default().with_options(Options::default().with_style(LanguageStyle::Racket));
+
+    assert_eq!(
+        writer
+            .write_to_string(&Value::from(Symbol::new("hello")))
+            .unwrap(),
+        "hello".to_string()
+    );
+    assert_eq!(
+        writer
+            .write_to_string(&Value::from(Symbol::new("x")))
+            .unwrap(),
+        "x".to_string()
+    );
+}
+
+#[test]
+fn test_print_symbol_escaped() {
+    let tests = [
+        ("a,b", LanguageStyle::Racket, "|a,b|"),
+        ("a`b", LanguageStyle::Scheme, "|a`b|"),
+        ("a\\b", LanguageStyle::CommonLisp, r"|a\\b|"),
+        ("a|b", LanguageStyle::Racket, r"|a\|b|"),
+        ("#a", LanguageStyle::EmacsLisp, "|#a|"),
+        ("#a", LanguageStyle::Racket, "|#a|"),
+        ("a[0]", LanguageStyle::EmacsLisp, "|a[0]|"),
+        ("a[0]", LanguageStyle::Scheme, "|a[0]|"),
+        ("{a}", LanguageStyle::Racket, "|{a}|"),
+        ("123", LanguageStyle::Racket, "|123|"),
+        ("-1.5", LanguageStyle::EmacsLisp, "|-1.5|"),
+        ("1/2", LanguageStyle::Scheme, "|1/2|"),
+        ("+inf.0", LanguageStyle::Racket, "|+inf.0|"),
+        ("1d0", LanguageStyle::CommonLisp, "|1d0|"),
+        (".", LanguageStyle::Scheme, "|.|"),
+        ("?a", LanguageStyle::EmacsLisp, "|?a|"),
+        ("Foo", LanguageStyle::CommonLisp, "|Foo|"),
+        ("#%1 2", LanguageStyle::Racket, "#%|1 2|"),
+    ];
+
+    for (name, style, expected) in tests {
+        assert!(Symbol::parse(name, style).is_err());
+        let writer = Writer::default().with_options(Options::default().with_style(style));
+        assert_eq!(
+            writer.write_to_string(&Symbol::new(name).into()).unwrap(),
+            expected.to_string(),
+            "writing {name:?} in {style:?}"
+        );
+    }
+
+    for (name, style) in [
+        ("{a}", LanguageStyle::Scheme),
+        ("1+", LanguageStyle::CommonLisp),
+        ("a#b", LanguageStyle::Racket),
+        ("#%app", LanguageStyle::Racket),
+    ] {
+        assert!(Symbol::parse(name, style).is_ok());
+        let writer = Writer::default().with_options(Options::default().with_style(style));
+        assert_eq!(
+            writer.write_to_string(&Symbol::new(name).into()).unwrap(),
+            name.to_string()
+        );
+    }
+}
+
+#[cfg(feature = "bignum")]
+#[test]
+fn test_print_big_integers() {
+    let writer =
+        Writer::default().with_options(Options::default().with_style(LanguageStyle::CommonLisp));
+
+    assert_eq!(
+        writer.write_to_string(&Value::from(u64::MAX)).unwrap(),
+        "18446744073709551615".to_string()
+    );
+    assert_eq!(
+        writer.write_to_string(&Value::from(i128::MIN)).unwrap(),
+        "-170141183460469231731687303715884105728".to_string()
+    );
+    assert_eq!(
+        writer.write_to_string(&Value::from(u128::MAX)).unwrap(),
+        "340282366920938463463374607431768211455".to_string()
+    );
+    assert!(sexpr_out::Number::from(42_u64).is_integer());
+    assert!(sexpr_out::Number::from(u64::MAX).is_big_integer());
+}
+
+#[test]
+fn test_print_rational() {
+    let third = Value::from(Rational::new(2, -6));
+
+    for style in [
+        LanguageStyle::Racket,
+        LanguageStyle::CommonLisp,
+        LanguageStyle::Scheme,
+    ] {
+        let writer = Writer::default().with_options(Options::default().with_style(style));
+
+        assert_eq!(writer.write_to_string(&third).unwrap(), "-1/3".to_string());
+    }
+}
+
+#[test]
+fn test_print_rational_elisp_fallback() {
+    let half = Value::from(Rational::new(1, 2));
+
+    let writer =
+        Writer::default().with_options(Options::default().with_style(LanguageStyle::EmacsLisp));
+    assert_eq!(writer.write_to_string(&half).unwrap(), "0.5".to_string());
+
+    let writer = Writer::default().with_options(
+        Options::default()
+            .with_style(LanguageStyle::EmacsLisp)
+            .with_rational_fallback(RationalFallback::Error),
+    );
+    assert!(writer.write_to_string(&half).is_err());
+}
+
+#[test]
+fn test_print_complex() {
+    let positive = Value::from(Complex::new(1.0, 2.0));
+    let negative = Value::from(Complex::new(1.5, -0.5));
+
+    for style in [LanguageStyle::Racket, LanguageStyle::Scheme] {
+        let writer = Writer::default().with_options(Options::default().with_style(style));
+
+        assert_eq!(
+            writer.write_to_string(&positive).unwrap(),
+            "1.0+2.0i".to_string()
+        );
+        assert_eq!(
+            writer.write_to_string(&negative).unwrap(),
+            "1.5-0.5i".to_string()
+        );
+    }
+
+    let writer =
+        Writer::default().with_options(Options::default().with_style(LanguageStyle::CommonLisp));
+    assert_eq!(
+        writer.write_to_string(&positive).unwrap(),
+        "#C(1.0 2.0)".to_string()
+    );
+    assert_eq!(
+        writer.write_to_string(&negative).unwrap(),
+        "#C(1.5 -0.5)".to_string()
+    );
+
+    for style in [LanguageStyle::EmacsLisp, LanguageStyle::TreeSitter] {
+        let writer = Writer::default().with_options(Options::default().with_style(style));
+
+        assert!(writer.write_to_string(&positive).is_err());
+    }
+}
+
+#[test]
+fn test_print_complex_non_finite() {
+    let nan = Value::from(Complex::new(1.0, f64::NAN));
+    let infinite = Value::from(Complex::new(f64::INFINITY, f64::NEG_INFINITY));
+
+    for style in [LanguageStyle::Racket, LanguageStyle::Scheme] {
+        let writer = Writer::default().with_options(Options::default().with_style(style));
+
+        assert_eq!(
+            writer.write_to_string(&nan).unwrap(),
+            "1.0+nan.0i".to_string()
+        );
+        assert_eq!(
+            writer.write_to_string(&infinite).unwrap(),
+            "+inf.0-inf.0i".to_string()
+        );
+    }
+
+    let writer =
+        Writer::default().with_options(Options::default().with_style(LanguageStyle::CommonLisp));
+    assert!(writer.write_to_string(&nan).is_err());
+    assert!(writer.write_to_string(&infinite).is_err());
+}
+
+#[test]
+fn test_print_bytes() {
+    let bytes = Value::bytes(vec![b'a', b'"', 0, 255]);
+
+    for (style, expected) in [
+        (LanguageStyle::Racket, r#"#"a\"\000\377""#),
+        (LanguageStyle::Scheme, "#u8(97 34 0 255)"),
+        (LanguageStyle::CommonLisp, "#(97 34 0 255)"),
+        (LanguageStyle::EmacsLisp, r#""a\"\000\377""#),
+    ] {
+        let writer = Writer::default().with_options(Options::default().with_style(style));
+
+        assert_eq!(
+            writer.write_to_string(&bytes).unwrap(),
+            expected.to_string()
+        );
+    }
+
+    let writer = Writer::default().with_options(
+        Options::default()
+            .with_style(LanguageStyle::CommonLisp)
+            .with_coerce_byte_vectors(true),
+    );
+    assert_eq!(
+        writer.write_to_string(&bytes).unwrap(),
+        "(coerce #(97 34 0 255) '(vector (unsigned-byte 8)))".to_string()
+    );
+
+    let writer =
+        Writer::default().with_options(Options::default().with_style(LanguageStyle::TreeSitter));
+    assert!(writer.write_to_string(&bytes).is_err());
+}
+
+#[test]
+fn test_print_quoted_short_form() {
+    let writer =
+        Writer::default().with_options(Options::default().with_style(LanguageStyle::Racket));
+
+    let template = Value::quasi_quote(vec![
+        Value::from(Symbol::new("a")),
+        Value::unquote(Symbol::new("b")),
+        Value::unquote_splicing(Symbol::new("c")),
+    ]);
+
+    assert_eq!(
+        writer
+            .write_to_string(&Value::quote(Symbol::new("x")))
+            .unwrap(),
+        "'x".to_string()
+    );
+    assert_eq!(
+        writer.write_to_string(&template).unwrap(),
+        "`(a ,b ,@c)".to_string()
+    );
+}
+
+#[test]
+fn test_print_quoted_long_form() {
+    let template = Value::quasi_quote(vec![
+        Value::from(Symbol::new("a")),
+        Value::unquote(Symbol::new("b")),
+        Value::unquote_splicing(Symbol::new("c")),
+    ]);
+
+    for (style, expected) in [
+        (
+            LanguageStyle::Racket,
+            "(quasiquote (a (unquote b) (unquote-splicing c)))",
+        ),
+        (LanguageStyle::CommonLisp, "`(a ,b ,@c)"),
+        (LanguageStyle::EmacsLisp, r"(\` (a (\, b) (\,@ c)))"),
+    ] {
+        let writer = Writer::default().with_options(
+            Options::default()
+                .with_style(style)
+                .with_quote(QuoteStyle::AsNeeded(true)),
+        );
+
+        assert_eq!(
+            writer.write_to_string(&template).unwrap(),
+            expected.to_string()
+        );
+    }
+
+    let writer = Writer::default().with_options(
+        Options::default()
+            .with_style(LanguageStyle::CommonLisp)
+            .with_quote(QuoteStyle::AsNeeded(true)),
+    );
+    assert_eq!(
+        writer
+            .write_to_string(&Value::quote(Symbol::new("x")))
+            .unwrap(),
+        "(quote x)".to_string()
+    );
+}
+
+#[test]
+fn test_print_quote_all() {
+    let writer = Writer::default().with_options(
+        Options::default()
+            .with_style(LanguageStyle::Racket)
+            .with_quote(QuoteStyle::All(false)),
+    );
+
+    assert_eq!(
+        writer.write_to_string(&Value::from(1)).unwrap(),
+        "'1".to_string()
+    );
+    assert_eq!(
+        writer
+            .write_to_string(&Value::from(vec![Value::from(1)]))
+            .unwrap(),
+        "'(1)".to_string()
+    );
+
+    let writer = Writer::default().with_options(
+        Options::default()
+            .with_style(LanguageStyle::Racket)
+            .with_quote(QuoteStyle::All(true)),
+    );
+
+    assert_eq!(
+        writer
+            .write_to_string(&Value::from(vec![Value::from(1)]))
+            .unwrap(),
+        "(quote (1))".to_string()
+    );
+}
+
+#[test]
+fn test_print_quote_as_needed() {
+    let list = Value::from(vec![Value::from(Symbol::new("a")), Value::from(1)]);
+
+    let writer = Writer::default().with_options(
+        Options::default()
+            .with_style(LanguageStyle::EmacsLisp)
+            .with_quote(QuoteStyle::AsNeeded(false)),
+    );
+
+    assert_eq!(writer.write_to_string(&list).unwrap(), "'(a 1)".to_string());
+    assert_eq!(
+        writer
+            .write_to_string(&Value::from(Symbol::new("a")))
+            .unwrap(),
+        "'a".to_string()
+    );
+    assert_eq!(
+        writer.write_to_string(&Value::from("a")).unwrap(),
+        "\"a\"".to_string()
+    );
+    assert_eq!(
+        writer
+            .write_to_string(&Value::from(Keyword::new("a")))
+            .unwrap(),
+        ":a".to_string()
+    );
+    assert_eq!(
+        writer.write_to_string(&Value::empty_list()).unwrap(),
+        "()".to_string()
+    );
+    assert_eq!(
+        writer
+            .write_to_string(&Value::vector([Value::from(1)]))
+            .unwrap(),
+        "[1]".to_string()
+    );
+
+    let writer = Writer::default().with_options(
+        Options::default()
+            .with_style(LanguageStyle::Racket)
+            .with_quote(QuoteStyle::AsNeeded(true)),
+    );
+
+    assert_eq!(
+        writer.write_to_string(&list).unwrap(),
+        "(quote (a 1))".to_string()
+    );
+    assert_eq!(
+        writer
+            .write_to_string(&Value::from(Keyword::new("a")))
+            .unwrap(),
+        "(quote #:a)".to_string()
+    );
+    assert_eq!(
+        writer.write_to_string(&Value::from(1)).unwrap(),
+        "1".to_string()
+    );
+}
+
+#[test]
+fn test_print_wrap_in_define() {
+    let list = Value::from(vec![Value::from(1), Value::from(2)]);
+
+    for (style, kind, expected) in [
+        (
+            LanguageStyle::Racket,
+            DefinitionKind::Variable,
+            "(define numbers '(1 2))",
+        ),
+        (
+            LanguageStyle::Scheme,
+            DefinitionKind::Constant,
+            "(define numbers '(1 2))",
+        ),
+        (
+            LanguageStyle::CommonLisp,
+            DefinitionKind::Variable,
+            "(defvar *numbers* '(1 2) \"Some numbers.\")",
+        ),
+        (
+            LanguageStyle::CommonLisp,
+            DefinitionKind::Parameter,
+            "(defparameter *numbers* '(1 2) \"Some numbers.\")",
+        ),
+        (
+            LanguageStyle::CommonLisp,
+            DefinitionKind::Constant,
+            "(defconstant numbers '(1 2) \"Some numbers.\")",
+        ),
+        (
+            LanguageStyle::EmacsLisp,
+            DefinitionKind::Variable,
+            "(defvar numbers '(1 2) \"Some numbers.\")",
+        ),
+        (
+            LanguageStyle::EmacsLisp,
+            DefinitionKind::Constant,
+            "(defconst numbers '(1 2) \"Some numbers.\")",
+        ),
+    ] {
+        let writer = Writer::default().with_options(
+            Options::default()
+                .with_style(style)
+                .with_wrap_in_define(true)
+                .with_definition_name("numbers")
+                .with_definition_kind(kind)
+                .with_definition_documentation("Some numbers."),
+        );
+
+        assert_eq!(writer.write_to_string(&list).unwrap(), expected.to_string());
+    }
+}
+
+#[test]
+fn test_print_wrap_in_define_documentation_escapes() {
+    let list = Value::from(vec![Value::from(1)]);
+    for (style, expected) in [
+        (LanguageStyle::Racket, "(define numbers '(1))"),
+        (LanguageStyle::Scheme, "(define numbers '(1))"),
+        (
+            LanguageStyle::CommonLisp,
+            "(defvar *numbers* '(1) \"a\u{7}b\nc \\\"d\\\"\")",
+        ),
+        (
+            LanguageStyle::EmacsLisp,
+            "(defvar numbers '(1) \"a\u{7}b\nc \\\"d\\\"\")",
+        ),
+    ] {
+        let writer = Writer::default().with_options(
+            Options::default()
+                .with_style(style)
+                .with_wrap_in_define(true)
+                .with_definition_name("numbers")
+                .with_definition_documentation("a\u{7}b\nc \"d\""),
+        );
+
+        assert_eq!(writer.write_to_string(&list).unwrap(), expected.to_string());
+    }
+
+    let writer = Writer::default().with_options(
+        Options::default()
+            .with_style(LanguageStyle::TreeSitter)
+            .with_wrap_in_define(true)
+            .with_definition_name("numbers")
+            .with_definition_documentation("a\u{7}b\nc"),
+    );
+    assert!(writer.write_to_string(&list).is_err());
+}
+
+#[test]
+fn test_print_wrap_in_define_errors() {
+    let writer = Writer::default().with_options(
+        Options::default()
+            .with_style(LanguageStyle::Racket)
+            .with_wrap_in_define(true),
+    );
+    assert!(writer.write_to_string(&Value::from(1)).is_err());
+
+    let writer = Writer::default().with_options(
+        Options::default()
+            .with_style(LanguageStyle::TreeSitter)
+            .with_wrap_in_define(true)
+            .with_definition_name("x"),
+    );
+    assert!(writer.write_to_string(&Value::from(1)).is_err());
+}
+
+#[test]
+fn test_print_comments() {
+    let value = Value::from(vec![
+        Value::from(1).with_trailing_comment(Comment::line(LineCommentLevel::Margin, "one")),
+        Value::from(2).with_leading_comment(Comment::block("two")),
+        Value::from(3).with_leading_comment(Comment::datum(Symbol::new("three"))),
+    ])
+    .with_leading_comment(Comment::line(LineCommentLevel::Section, "DO NOT EDIT"));
+
+    let writer =
+        Writer::default().with_options(Options::default().with_style(LanguageStyle::Racket));
+    assert_eq!(
+        writer.write_to_string(&value).unwrap(),
+        ";;; DO NOT EDIT\n(1 ; one\n #| two |# 2 #;three 3)".to_string()
+    );
+
+    let writer =
+        Writer::default().with_options(Options::default().with_style(LanguageStyle::CommonLisp));
+    assert!(writer.write_to_string(&value).is_err());
+
+    let writer =
+        Writer::default().with_options(Options::default().with_style(LanguageStyle::TreeSitter));
+    assert!(writer.write_to_string(&value).is_err());
+}
+
+#[test]
+fn test_print_block_comment_delimiters() {
+    let writer =
+        Writer::default().with_options(Options::default().with_style(LanguageStyle::Racket));
+
+    for text in ["early |# end", "nested #| start"] {
+        let value = Value::from(1).with_leading_comment(Comment::block(text));
+        assert!(writer.write_to_string(&value).is_err());
+    }
+    let value = Value::from(1).with_leading_comment(Comment::block("a | b # c"));
+    assert_eq!(
+        writer.write_to_string(&value).unwrap(),
+        "#| a | b # c |# 1".to_string()
+    );
+}
+
+#[test]
+fn test_print_comments_outside_quote() {
+    let value = Value::from(Symbol::new("x"))
+        .with_leading_comment(Comment::line(LineCommentLevel::Code, "first\nsecond"));
+
+    let writer = Writer::default().with_options(
+        Options::default()
+            .with_style(LanguageStyle::EmacsLisp)
+            .with_quote(QuoteStyle::AsNeeded(false)),
+    );
+    assert_eq!(
+        writer.write_to_string(&value).unwrap(),
+        ";; first\n;; second\n'x".to_string()
+    );
+}
+
+#[test]
+fn test_print_racket_literals() {
+    let writer =
+        Writer::default().with_options(Options::default().with_style(LanguageStyle::Racket));
+
+    let tests: Vec<(Value, &str)> = vec![
+        (Value::boxed(1), "#&1"),
+        (
+            Struct::new(Symbol::new("point"), [Value::from(1), Value::from(2)]).into(),
+            "#s(point 1 2)",
+        ),
+        (Regexp::new("a|b").into(), r#"#rx"a|b""#),
+        (
+            Regexp::new(r"\d+").with_syntax(RegexpSyntax::Perl).into(),
+            r#"#px"\\d+""#,
+        ),
+        (
+            Regexp::new("a*").with_byte_regexp(true).into(),
+            r#"#rx#"a*""#,
+        ),
+        (PathBuf::from("/tmp/x").into(), r#"(string->path "/tmp/x")"#),
+        (
+            PathBuf::from(r#"/tmp/a|b "c"\d"#).into(),
+            r#"(string->path "/tmp/a|b \"c\"\\d")"#,
+        ),
+    ];
+
+    for (value, expected) in tests {
+        assert_eq!(
+            writer.write_to_string(&value).unwrap(),
+            expected.to_string()
+        );
+    }
+}
+
+#[test]
+fn test_print_racket_path_quoted() {
+    let path = Value::from(PathBuf::from("/tmp/x"));
+    let call = Value::from(vec![Value::from(Symbol::new("open")), path.clone()]);
+
+    let writer =
+        Writer::default().with_options(Options::default().with_style(LanguageStyle::Racket));
+    assert_eq!(
+        writer.write_to_string(&call).unwrap(),
+        r#"(open (string->path "/tmp/x"))"#.to_string()
+    );
+    assert!(writer
+        .write_to_string(&Value::vector(vec![path.clone()]))
+        .is_err());
+    assert!(writer.write_to_string(&Value::quote(call.clone())).is_err());
+    assert_eq!(
+        writer
+            .write_to_string(&Value::quasi_quote(vec![
+                Value::from(Symbol::new("a")),
+                Value::unquote(path.clone()),
+            ]))
+            .unwrap(),
+        r#"`(a ,(string->path "/tmp/x"))"#.to_string()
+    );
+
+    for quote in [QuoteStyle::All(false), QuoteStyle::AsNeeded(false)] {
+        let writer = Writer::default().with_options(
+            Options::default()
+                .with_style(LanguageStyle::Racket)
+                .with_quote(quote),
+        );
+        assert!(writer.write_to_string(&call).is_err());
+    }
+}
+
+#[test]
+fn test_print_racket_literals_unsupported() {
+    let values: Vec<Value> = vec![Value::boxed(1), Regexp::new("a").into()];
+
+    for style in [
+        LanguageStyle::CommonLisp,
+        LanguageStyle::Scheme,
+        LanguageStyle::EmacsLisp,
+        LanguageStyle::TreeSitter,
+    ] {
+        let writer = Writer::default().with_options(Options::default().with_style(style));
+        for value in &values {
+            assert!(writer.write_to_string(value).is_err());
+        }
+        if style != LanguageStyle::CommonLisp {
+            assert!(writer
+                .write_to_string(&PathBuf::from("/tmp/x").into())
+                .is_err());
+        }
+        if style != LanguageStyle::EmacsLisp {
+            assert!(writer
+                .write_to_string(&Struct::new(Symbol::new("point"), [Value::from(1)]).into())
+                .is_err());
+        }
+    }
+}
+
+#[test]
+fn test_print_common_lisp_literals() {
+    let writer =
+        Writer::default().with_options(Options::default().with_style(LanguageStyle::CommonLisp));
+
+    let tests: Vec<(Value, &str)> = vec![
+        (PathBuf::from("/tmp/x").into(), r#"#P"/tmp/x""#),
+        (Value::bit_vector([true, false, true, true]), "#*1011"),
+        (Value::bit_vector([]), "#*"),
+        (
+            Array::new(vec![2, 2], [1, 2, 3, 4].map(Value::from))
+                .unwrap()
+                .into(),
+            "#2A((1 2) (3 4))",
+        ),
+        (Array::new(vec![2, 0], []).unwrap().into(), "#2A(() ())"),
+        (Array::new(vec![], [Value::from(1)]).unwrap().into(), "#0A1"),
+        (Value::function(Symbol::new("car")), "#'car"),
+    ];
+
+    for (value, expected) in tests {
+        assert_eq!(
+            writer.write_to_string(&value).unwrap(),
+            expected.to_string()
+        );
+    }
+}
+
+#[test]
+fn test_print_function_reference() {
+    let value = Value::function(Symbol::new("car"));
+
+    let writer = Writer::default().with_options(
+        Options::default()
+            .with_style(LanguageStyle::EmacsLisp)
+            .with_quote(QuoteStyle::None),
+    );
+    assert_eq!(writer.write_to_string(&value).unwrap(), "#'car".to_string());
+
+    let writer = Writer::default().with_options(
+        Options::default()
+            .with_style(LanguageStyle::CommonLisp)
+            .with_quote(QuoteStyle::All(true)),
+    );
+    assert_eq!(
+        writer.write_to_string(&value).unwrap(),
+        "(quote (function car))".to_string()
+    );
+
+    for style in [LanguageStyle::Racket, LanguageStyle::Scheme] {
+        let writer = Writer::default().with_options(Options::default().with_style(style));
+        assert!(writer.write_to_string(&value).is_err());
+    }
+}
+
+#[test]
+fn test_print_common_lisp_literals_unsupported() {
+    let values: Vec<Value> = vec![Array::new(vec![1], [Value::from(1)]).unwrap().into()];
+
+    for style in [
+        LanguageStyle::Racket,
+        LanguageStyle::Scheme,
+        LanguageStyle::EmacsLisp,
+        LanguageStyle::TreeSitter,
+    ] {
+        let writer = Writer::default().with_options(Options::default().with_style(style));
+        for value in &values {
+            assert!(writer.write_to_string(value).is_err());
+        }
+        if style != LanguageStyle::EmacsLisp {
+            assert!(writer.write_to_string(&Value::bit_vector([true])).is_err());
+        }
+    }
+}
+
+#[test]
+fn test_print_emacs_lisp_literals() {
+    let writer =
+        Writer::default().with_options(Options::default().with_style(LanguageStyle::EmacsLisp));
+
+    let tests: Vec<(Value, &str)> = vec![
+        (
+            Struct::new(Symbol::new("point"), [Value::from(1), Value::from(2)]).into(),
+            "#s(point 1 2)",
+        ),
+        (
+            PropertizedString::new("text")
+                .with_properties(
+                    TextProperties::new(0, 4)
+                        .unwrap()
+                        .with_property(Symbol::new("face"), Symbol::new("bold")),
+                )
+                .unwrap()
+                .into(),
+            r#"#("text" 0 4 (face bold))"#,
+        ),
+        (
+            ModifiedChar::new('a')
+                .with_modifier(CharModifier::Control)
+                .into(),
+            r"?\C-a",
+        ),
+        (
+            ModifiedChar::new('x')
+                .with_modifier(CharModifier::Control)
+                .with_modifier(CharModifier::Meta)
+                .into(),
+            r"?\C-\M-x",
+        ),
+        (
+            ModifiedChar::new('z')
+                .with_modifier(CharModifier::Super)
+                .into(),
+            r"?\s-z",
+        ),
+        (
+            ModifiedChar::new('(')
+                .with_modifier(CharModifier::Hyper)
+                .into(),
+            r"?\H-\(",
+        ),
+        (Value::bit_vector([true; 5]), r#"#&5"\037""#),
+        (
+            Value::bit_vector([true, false, false, false, false, false, true, false, true]),
+            r#"#&9"A\001""#,
+        ),
+    ];
+
+    for (value, expected) in tests {
+        assert_eq!(
+            writer.write_to_string(&value).unwrap(),
+            expected.to_string()
+        );
+    }
+}
+
+#[test]
+fn test_print_emacs_lisp_literals_unsupported() {
+    let values: Vec<Value> = vec![
+        ModifiedChar::new('a')
+            .with_modifier(CharModifier::Meta)
+            .into(),
+        PropertizedString::new("text").into(),
+    ];
+
+    for style in [
+        LanguageStyle::Racket,
+        LanguageStyle::CommonLisp,
+        LanguageStyle::Scheme,
+        LanguageStyle::TreeSitter,
+    ] {
+        let writer = Writer::default().with_options(Options::default().with_style(style));
+        for value in &values {
+            assert!(writer.write_to_string(value).is_err());
+        }
+    }
+}
+
+#[test]
+fn test_print_tree_sitter_nodes() {
+    let writer =
+        Writer::default().with_options(Options::default().with_style(LanguageStyle::TreeSitter));
+
+    let tests: Vec<(Node, &str)> = vec![
+        (Node::named("identifier"), "(identifier)"),
+        (
+            Node::named("identifier").with_field("name"),
+            "name: (identifier)",
+        ),
+        (Node::anonymous("("), r#""(""#),
+        (Node::anonymous("\"\n"), r#""\"\n""#),
+        (Node::missing_named("identifier"), "(MISSING identifier)"),
+        (Node::missing_anonymous(";"), r#"(MISSING ";")"#),
+        (
+            Node::error().with_child(Node::named("identifier")),
+            "(ERROR (identifier))",
+        ),
+        (
+            Node::named("call")
+                .with_range((0, 0), (0, 6))
+                .with_child(
+                    Node::named("identifier")
+                        .with_field("function")
+                        .with_range((0, 0), (0, 4)),
+                )
+                .with_child(Node::named("arguments").with_field("arguments")),
+            "(call [0, 0] - [0, 6] function: (identifier [0, 0] - [0, 4]) arguments: (arguments))",
+        ),
+    ];
+
+    for (node, expected) in tests {
+        assert_eq!(
+            writer.write_to_string(&node.into()).unwrap(),
+            expected.to_string()
+        );
+    }
+
+    let writer =
+        Writer::default().with_options(Options::default().with_style(LanguageStyle::Racket));
+    assert!(writer
+        .write_to_string(&Node::named("identifier").into())
+        .is_err());
+}
+
+#[test]
+fn test_print_qualified_symbols() {
+    let tests: Vec<(Symbol, LanguageStyle, &str)> = vec![
+        (
+            Symbol::external("cl", "car"),
+            LanguageStyle::CommonLisp,
+            "cl:car",
+        ),
+        (
+            Symbol::internal("my-pkg", "helper"),
+            LanguageStyle::CommonLisp,
+            "my-pkg::helper",
+        ),
+        (Symbol::uninterned("g1"), LanguageStyle::CommonLisp, "#:g1"),
+        (Symbol::uninterned("g1"), LanguageStyle::EmacsLisp, "#:g1"),
+        (
+            Symbol::namespaced("string", "join"),
+            LanguageStyle::Racket,
+            "string/join",
+        ),
+        (
+            Symbol::namespaced("srfi", "list"),
+            LanguageStyle::Scheme,
+            "srfi/list",
+        ),
+        (
+            Symbol::namespaced("my-pkg", "helper"),
+            LanguageStyle::EmacsLisp,
+            "my-pkg/helper",
+        ),
+        (
+            Symbol::internal("my pkg", "a|b"),
+            LanguageStyle::CommonLisp,
+            r"|my pkg|::|a\|b|",
+        ),
+        (
+            Symbol::new("pkg::sym"),
+            LanguageStyle::CommonLisp,
+            "|pkg::sym|",
+        ),
+        (Symbol::new("pkg::sym"), LanguageStyle::Racket, "pkg::sym"),
+        (Symbol::new("#%app"), LanguageStyle::Racket, "#%app"),
+        (Symbol::new("#%a b"), LanguageStyle::Racket, "#%|a b|"),
+        (
+            Symbol::external("pkg", "bell\u{7}"),
+            LanguageStyle::CommonLisp,
+            "pkg:|bell\u{7}|",
+        ),
+    ];
+
+    for (symbol, style, expected) in tests {
+        let writer = Writer::default().with_options(Options::default().with_style(style));
+        assert_eq!(
+            writer.write_to_string(&symbol.into()).unwrap(),
+            expected.to_string()
+        );
+    }
+
+    let writer =
+        Writer::default().with_options(Options::default().with_style(LanguageStyle::Racket));
+    assert!(writer
+        .write_to_string(&Symbol::external("cl", "car").into())
+        .is_err());
+    assert!(writer
+        .write_to_string(&Symbol::uninterned("g1").into())
+        .is_err());
+
+    for style in [LanguageStyle::CommonLisp, LanguageStyle::TreeSitter] {
+        let writer = Writer::default().with_options(Options::default().with_style(style));
+        assert!(writer
+            .write_to_string(&Symbol::namespaced("string", "join").into())
+            .is_err());
+    }
+}
+
+#[test]
+fn test_print_shared_values() {
+    let writer =
+        Writer::default().with_options(Options::default().with_style(LanguageStyle::Racket));
+
+    let shared = Shared::new(Value::from(vec![Value::from(1), Value::from(2)]));
+    let value = Value::from(vec![Value::from(&shared), Value::from(&shared)]);
+    assert_eq!(
+        writer.write_to_string(&value).unwrap(),
+        "(#0=(1 2) #0#)".to_string()
+    );
+
+    let once = Value::from(vec![Value::from(&shared), Value::from(3)]);
+    assert_eq!(
+        writer.write_to_string(&once).unwrap(),
+        "((1 2) 3)".to_string()
+    );
+
+    let cycle = Shared::empty();
+    cycle.set(Value::pair(Symbol::new("a"), &cycle)).unwrap();
+    let writer =
+        Writer::default().with_options(Options::default().with_style(LanguageStyle::CommonLisp));
+    assert_eq!(
+        writer.write_to_string(&Value::from(&cycle)).unwrap(),
+        "#0=(a . #0#)".to_string()
+    );
+
+    let writer =
+        Writer::default().with_options(Options::default().with_style(LanguageStyle::TreeSitter));
+    assert!(writer.write_to_string(&value).is_err());
+    assert!(writer
+        .write_to_string(&Value::from(Shared::empty()))
+        .is_err());
+}
+
+#[test]
+fn test_print_shared_cycles_through_containers() {
+    fn cycle<F>(build: F) -> Value
+    where
+        F: Fn(Value) -> Value,
+    {
+        let shared = Shared::empty();
+        shared.set(build(Value::from(&shared))).unwrap();
+        Value::from(&shared)
+    }
+
+    let tests = [
+        (
+            LanguageStyle::Racket,
+            cycle(|v| Value::from(vec![Value::from(1), v])),
+        ),
+        (
+            LanguageStyle::Racket,
+            cycle(|v| Value::improper_list(vec![v], Value::from(1))),
+        ),
+        (LanguageStyle::Racket, cycle(|v| Value::vector(vec![v]))),
+        (
+            LanguageStyle::Racket,
+            cycle(|v| {
+                let mut map = Map::new();
+                map.insert(Value::from("k"), v);
+                map.into()
+            }),
+        ),
+        (
+            LanguageStyle::Racket,
+            cycle(|v| Value::from(vec![Value::quote(v)])),
+        ),
+        (
+            LanguageStyle::Racket,
+            cycle(|v| {
+                Value::from(vec![v.with_trailing_comment(Comment::line(
+                    LineCommentLevel::Margin,
+                    "self",
+                ))])
+            }),
+        ),
+        (LanguageStyle::Racket, cycle(Value::boxed)),
+        (
+            LanguageStyle::Racket,
+            cycle(|v| Struct::new(Symbol::new("node"), vec![v]).into()),
+        ),
+        (
+            LanguageStyle::CommonLisp,
+            cycle(|v| Array::new(vec![1, 1], vec![v]).unwrap().into()),
+        ),
+        (
+            LanguageStyle::EmacsLisp,
+            cycle(|v| {
+                PropertizedString::new("ab")
+                    .with_properties(
+                        TextProperties::new(0, 1)
+                            .unwrap()
+                            .with_property(Symbol::new("self"), v),
+                    )
+                    .unwrap()
+                    .into()
+            }),
+        ),
+    ];
+
+    for (style, value) in tests {
+        let writer = Writer::default().with_options(Options::default().with_style(style));
+        let written = writer.write_to_string(&value).unwrap();
+        assert!(
+            written.starts_with("#0=") && written.contains("#0#"),
+            "unexpected {written:?} in {style:?}"
+        );
+    }
+
+    let shared = Shared::new(Value::from("shared"));
+    let value = Value::from(
+        PropertizedString::new("ab")
+            .with_properties(
+                TextProperties::new(0, 1)
+                    .unwrap()
+                    .with_property(Symbol::new("a"), &shared)
+                    .with_property(Symbol::new("b"), &shared),
+            )
+            .unwrap(),
+    );
+    let writer =
+        Writer::default().with_options(Options::default().with_style(LanguageStyle::EmacsLisp));
+    assert_eq!(
+        writer.write_to_string(&value).unwrap(),
+        "#(\"ab\" 0 1 (a #0=\"shared\" b #0#))".to_string()
+    );
+}
+
+#[test]
+fn test_print_datum_labels() {
+    let writer =
+        Writer::default().with_options(Options::default().with_style(LanguageStyle::EmacsLisp));
+
+    let value = Value::from(vec![
+        DatumLabel::define(1, Value::from("x")).into(),
+        DatumLabel::reference(1).into(),
+    ]);
+    assert_eq!(
+        writer.write_to_string(&value).unwrap(),
+        r#"(#1="x" #1#)"#.to_string()
+    );
+}
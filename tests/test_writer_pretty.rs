@@ -1,6 +1,6 @@
 use objio::{HasOptions, ObjectWriter};
 use pretty_assertions::assert_eq;
-use sexpr_out::value::Value;
+use sexpr_out::value::{Comment, Keyword, LineCommentLevel, Node, Symbol, Value};
 use sexpr_out::writer::{LanguageStyle, Options, Writer};
 
 #[test]
@@ -67,3 +67,270 @@ fn test_pretty_print_short_nested_list_racket() {
         "(1 2 3\n (4 5 (6 7 (8))) 9\n 10)\n".to_string()
     );
 }
+
+#[test]
+fn test_pretty_print_improper_list_racket() {
+    let writer = Writer::default().pretty_printed(true).with_options(
+        Options::default()
+            .with_line_width(20)
+            .with_style(LanguageStyle::Racket),
+    );
+
+    let list = Value::improper_list(
+        vec![
+            Value::from("hello"),
+            Value::from("this"),
+            Value::from("is"),
+            Value::from("a"),
+        ],
+        Value::from("pair"),
+    );
+
+    //           1         2         3
+    // 0123456789012345678901234567890
+    // ("hello" "this" "is"
+    //  "a" . "pair")
+
+    assert_eq!(
+        writer.write_to_string(&list).unwrap(),
+        "(\"hello\" \"this\" \"is\"\n \"a\" . \"pair\")\n".to_string()
+    );
+}
+
+#[test]
+fn test_pretty_print_nested_vector_elisp() {
+    let writer = Writer::default().pretty_printed(true).with_options(
+        Options::default()
+            .with_line_width(20)
+            .with_style(LanguageStyle::EmacsLisp),
+    );
+
+    let list = Value::from(vec![
+        Value::from(1),
+        Value::from(2),
+        Value::vector([
+            Value::from(3),
+            Value::from(4),
+            Value::from(5),
+            Value::from(6),
+            Value::from(7),
+            Value::from(8),
+            Value::from(9),
+            Value::from(10),
+        ]),
+    ]);
+
+    //           1         2         3
+    // 0123456789012345678901234567890
+    // (1 2
+    //  [3 4 5 6 7 8 9 10])
+
+    assert_eq!(
+        writer.write_to_string(&list).unwrap(),
+        "(1 2\n [3 4 5 6 7 8 9 10])\n".to_string()
+    );
+}
+
+#[test]
+fn test_pretty_print_wrap_in_define_elisp() {
+    let writer = Writer::default().pretty_printed(true).with_options(
+        Options::default()
+            .with_line_width(30)
+            .with_style(LanguageStyle::EmacsLisp)
+            .with_wrap_in_define(true)
+            .with_definition_name("greeting")
+            .with_definition_documentation("A greeting."),
+    );
+
+    let list = Value::from(vec![
+        Value::from("hello"),
+        Value::from("this"),
+        Value::from("is"),
+        Value::from("a"),
+        Value::from("list"),
+    ]);
+
+    //           1         2         3
+    // 0123456789012345678901234567890
+    // (defvar greeting
+    //   '("hello" "this" "is" "a"
+    //     "list")
+    //   "A greeting.")
+
+    assert_eq!(
+        writer.write_to_string(&list).unwrap(),
+        "(defvar greeting\n  '(\"hello\" \"this\" \"is\" \"a\"\n    \"list\")\n  \"A greeting.\")\n"
+            .to_string()
+    );
+}
+
+fn make_widget() -> Value {
+    Value::from(vec![
+        Value::from(Symbol::new("make-widget")),
+        Value::from(Keyword::new("name")),
+        Value::from("x"),
+        Value::from(Keyword::new("width")),
+        Value::from(10),
+        Value::from(Keyword::new("height")),
+        Value::from(20),
+    ])
+}
+
+#[test]
+fn test_pretty_print_kw_args_unpaired() {
+    let writer = Writer::default().pretty_printed(true).with_options(
+        Options::default()
+            .with_line_width(28)
+            .with_style(LanguageStyle::CommonLisp),
+    );
+
+    //           1         2         3
+    // 0123456789012345678901234567890
+    // (make-widget :name "x" :width
+    //  10 :height 20)
+
+    assert_eq!(
+        writer.write_to_string(&make_widget()).unwrap(),
+        "(make-widget :name \"x\" :width\n 10 :height 20)\n".to_string()
+    );
+}
+
+#[test]
+fn test_pretty_print_kw_args_paired() {
+    let writer = Writer::default().pretty_printed(true).with_options(
+        Options::default()
+            .with_line_width(28)
+            .with_style(LanguageStyle::CommonLisp)
+            .with_pair_kw_args(true),
+    );
+
+    //           1         2         3
+    // 0123456789012345678901234567890
+    // (make-widget :name "x"
+    //  :width 10 :height 20)
+
+    assert_eq!(
+        writer.write_to_string(&make_widget()).unwrap(),
+        "(make-widget :name \"x\"\n :width 10 :height 20)\n".to_string()
+    );
+}
+
+#[test]
+fn test_pretty_print_kw_args_aligned() {
+    let writer = Writer::default().pretty_printed(true).with_options(
+        Options::default()
+            .with_line_width(30)
+            .with_style(LanguageStyle::CommonLisp)
+            .with_pair_kw_args(true)
+            .with_align_kw_args(true),
+    );
+
+    //           1         2         3
+    // 0123456789012345678901234567890
+    // (make-widget :name   "x"
+    //              :width  10
+    //              :height 20)
+
+    assert_eq!(
+        writer.write_to_string(&make_widget()).unwrap(),
+        "(make-widget :name   \"x\"\n             :width  10\n             :height 20)\n"
+            .to_string()
+    );
+}
+
+#[test]
+fn test_pretty_print_trailing_comments_aligned() {
+    let writer = Writer::default().pretty_printed(true).with_options(
+        Options::default()
+            .with_line_width(80)
+            .with_style(LanguageStyle::Racket),
+    );
+
+    let value = Value::from(vec![
+        Value::pair(Symbol::new("a"), 1)
+            .with_trailing_comment(Comment::line(LineCommentLevel::Margin, "first")),
+        Value::pair(Symbol::new("bbb"), 2)
+            .with_trailing_comment(Comment::line(LineCommentLevel::Margin, "second")),
+        Value::pair(Symbol::new("c"), 3)
+            .with_leading_comment(Comment::line(LineCommentLevel::Code, "the last")),
+    ])
+    .with_leading_comment(Comment::line(LineCommentLevel::Section, "DO NOT EDIT"));
+
+    assert_eq!(
+        writer.write_to_string(&value).unwrap(),
+        r#";;; DO NOT EDIT
+((a . 1)   ; first
+ (bbb . 2) ; second
+ ;; the last
+ (c . 3))
+"#
+        .to_string()
+    );
+}
+
+#[test]
+fn test_pretty_print_trailing_comment_last() {
+    let writer = Writer::default().pretty_printed(true).with_options(
+        Options::default()
+            .with_line_width(80)
+            .with_style(LanguageStyle::Scheme),
+    );
+
+    let value = Value::from(vec![
+        Value::from(1),
+        Value::from(2).with_trailing_comment(Comment::line(LineCommentLevel::Margin, "two")),
+    ]);
+
+    assert_eq!(
+        writer.write_to_string(&value).unwrap(),
+        "(1\n 2 ; two\n )\n".to_string()
+    );
+}
+
+#[test]
+fn test_pretty_print_tree_sitter_nodes() {
+    let writer = Writer::default().pretty_printed(true).with_options(
+        Options::default()
+            .with_line_width(80)
+            .with_style(LanguageStyle::TreeSitter),
+    );
+
+    let tree = Node::named("source_file").with_child(
+        Node::named("function_item")
+            .with_child(Node::named("identifier").with_field("name"))
+            .with_child(Node::named("parameters").with_field("parameters"))
+            .with_child(
+                Node::named("block")
+                    .with_field("body")
+                    .with_child(Node::missing_anonymous("}")),
+            ),
+    );
+
+    assert_eq!(
+        writer.write_to_string(&tree.into()).unwrap(),
+        r#"(source_file
+  (function_item
+    name: (identifier)
+    parameters: (parameters)
+    body: (block
+      (MISSING "}"))))
+"#
+        .to_string()
+    );
+}
+
+#[test]
+fn test_pretty_print_unsupported_item_is_error() {
+    let writer = Writer::default().pretty_printed(true).with_options(
+        Options::default()
+            .with_line_width(20)
+            .with_style(LanguageStyle::TreeSitter),
+    );
+
+    let list = Value::from(vec![
+        Value::from(Symbol::new("source_file")),
+        Value::vector(vec![Value::from(1), Value::from(2), Value::from(3)]),
+    ]);
+
+    assert!(writer.write_to_string(&list).is_err());
+}